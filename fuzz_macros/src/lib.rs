@@ -63,14 +63,14 @@ fn define_anyvalue_impl(input: Punctuated<syn::Type, syn::Token![,]>) -> TokenSt
         }
 
         impl<const CHILD_INDEX: usize> lexord::LexOrdSer for AnyValue<CHILD_INDEX> {
-            fn to_write(&self, writer: &mut impl std::io::Write) -> lexord::Result {
+            fn to_write(&self, writer: &mut impl lexord::io::Sink) -> lexord::Result {
                 Self::with(|type_id: u16| {
                     match &*self.0 {
                         #(AnyValueEnum::#variant(value) => <#ty as lexord::LexOrdSer>::to_write(value, writer),)*
                     }
                 })
             }
-            fn to_write_seq(&self, writer: &mut impl std::io::Write) -> lexord::Result {
+            fn to_write_seq(&self, writer: &mut impl lexord::io::Sink) -> lexord::Result {
                 Self::with(|type_id: u16| {
                     match &*self.0 {
                         #(AnyValueEnum::#variant(value) => <#ty as lexord::LexOrdSer>::to_write_seq(value, writer),)*
@@ -80,7 +80,7 @@ fn define_anyvalue_impl(input: Punctuated<syn::Type, syn::Token![,]>) -> TokenSt
         }
 
         impl<const CHILD_INDEX: usize> lexord::LexOrd for AnyValue<CHILD_INDEX> {
-            fn from_read(reader: &mut impl std::io::Read) -> lexord::Result<Self> {
+            fn from_read(reader: &mut impl lexord::io::Source) -> lexord::Result<Self> {
                 Self::with(|type_id: u16| {
                     match type_id {
                         #(#i => Ok(Self(Box::new(AnyValueEnum::#variant(<#ty as lexord::LexOrd>::from_read(reader)?)))),)*
@@ -88,7 +88,7 @@ fn define_anyvalue_impl(input: Punctuated<syn::Type, syn::Token![,]>) -> TokenSt
                     }
                 })
             }
-            fn from_read_seq(first: u8, reader: &mut impl std::io::Read) -> lexord::Result<Self> {
+            fn from_read_seq(first: u8, reader: &mut impl lexord::io::Source) -> lexord::Result<Self> {
                 Self::with(|type_id: u16| {
                     match type_id {
                         #(#i => Ok(Self(Box::new(AnyValueEnum::#variant(<#ty as lexord::LexOrd>::from_read_seq(first, reader)?)))),)*
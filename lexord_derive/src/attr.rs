@@ -0,0 +1,243 @@
+use proc_macro2::Span;
+use syn::{
+    parenthesized, parse::ParseStream, punctuated::Punctuated, spanned::Spanned, token, Attribute,
+    Expr, ExprLit, Ident, Lit, LitInt, LitStr, Member, Path, Token, Type, WherePredicate,
+};
+
+#[derive(Default)]
+pub struct ContainerAttrs {
+    pub reserved: Vec<(usize, LitInt)>,
+    pub bound: BoundAttrs,
+    pub cmp: CmpMode,
+    pub encodes_as: Vec<Type>,
+    pub transparent: Option<Span>,
+    pub order: Option<Vec<Member>>,
+    pub indexes: Vec<IndexAttrs>,
+}
+
+pub struct IndexAttrs {
+    pub name: Ident,
+    pub fields: Vec<Member>,
+    pub derives: Vec<Path>,
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum CmpMode {
+    #[default]
+    Partial,
+    None,
+    Total,
+}
+
+#[derive(Default)]
+pub struct BoundAttrs {
+    pub ord: Option<Vec<WherePredicate>>,
+    pub ser: Option<Vec<WherePredicate>>,
+    pub de: Option<Vec<WherePredicate>>,
+    pub de_ref: Option<Vec<WherePredicate>>,
+}
+
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub desc: bool,
+    pub skip: bool,
+    pub default: Option<DefaultValue>,
+    pub added_in: Option<usize>,
+    pub with: Option<Path>,
+    pub position: Option<usize>,
+}
+
+#[derive(Clone)]
+pub enum DefaultValue {
+    Trait,
+    Path(Path),
+}
+
+#[derive(Default)]
+pub struct VariantAttrs {
+    pub tag: Option<usize>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = ContainerAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("lexord")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("reserved") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    for lit in Punctuated::<LitInt, Token![,]>::parse_terminated(&content)? {
+                        result.reserved.push((lit.base10_parse()?, lit));
+                    }
+                    Ok(())
+                } else if meta.path.is_ident("no_cmp") || meta.path.is_ident("ord") {
+                    if result.cmp != CmpMode::Partial {
+                        return Err(meta.error("`no_cmp` and `ord` can only be given once"));
+                    }
+                    // With `no_cmp` the type implements `PartialEq` and `PartialOrd` by hand,
+                    // and has to agree with the encoding, which `util::test::check_order` tests.
+                    result.cmp = match meta.path.is_ident("ord") {
+                        true => CmpMode::Total,
+                        false => CmpMode::None,
+                    };
+                    Ok(())
+                } else if meta.path.is_ident("order") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    let members = Punctuated::<Member, Token![,]>::parse_terminated(&content)?;
+                    result.order = Some(members.into_iter().collect());
+                    Ok(())
+                } else if meta.path.is_ident("indexes") {
+                    // `indexes(by_email(email, id), by_age(age, id, derive(Debug)))` gives
+                    // each index its fields in key order, plus derives for its key struct.
+                    // The key structs are items next to the type rather than inside its
+                    // `const _` block, so they can be named, and generic types are rejected.
+                    meta.parse_nested_meta(|index| {
+                        let name = index.path.require_ident()?.clone();
+                        let content;
+                        parenthesized!(content in index.input);
+                        let mut fields = vec![];
+                        let mut derives = vec![];
+                        while !content.is_empty() {
+                            let member = content.parse::<Member>()?;
+                            match member {
+                                Member::Named(ident)
+                                    if ident == "derive" && content.peek(token::Paren) =>
+                                {
+                                    let paths;
+                                    parenthesized!(paths in content);
+                                    derives.extend(
+                                        Punctuated::<Path, Token![,]>::parse_terminated(&paths)?,
+                                    );
+                                }
+                                member => fields.push(member),
+                            }
+                            if !content.is_empty() {
+                                content.parse::<Token![,]>()?;
+                            }
+                        }
+                        result.indexes.push(IndexAttrs {
+                            name,
+                            fields,
+                            derives,
+                        });
+                        Ok(())
+                    })
+                } else if meta.path.is_ident("transparent") {
+                    result.transparent = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("encodes_as") {
+                    let value = meta.value()?;
+                    result.encodes_as.push(match value.peek(LitStr) {
+                        true => value.parse::<LitStr>()?.parse()?,
+                        false => value.parse()?,
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("bound") && meta.input.peek(Token![=]) {
+                    let bound = parse_bound(meta.value()?)?;
+                    result.bound = BoundAttrs {
+                        ord: Some(bound.clone()),
+                        ser: Some(bound.clone()),
+                        de: Some(bound.clone()),
+                        de_ref: Some(bound),
+                    };
+                    Ok(())
+                } else if meta.path.is_ident("bound") {
+                    meta.parse_nested_meta(|meta| {
+                        let bound = match meta.path.get_ident() {
+                            Some(ident) if ident == "ord" => &mut result.bound.ord,
+                            Some(ident) if ident == "ser" => &mut result.bound.ser,
+                            Some(ident) if ident == "de" => &mut result.bound.de,
+                            Some(ident) if ident == "de_ref" => &mut result.bound.de_ref,
+                            _ => return Err(meta.error("expected `ord`, `ser`, `de` or `de_ref`")),
+                        };
+                        *bound = Some(parse_bound(meta.value()?)?);
+                        Ok(())
+                    })
+                } else {
+                    Err(meta.error("unsupported lexord container attribute"))
+                }
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+impl VariantAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = VariantAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("lexord")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag") {
+                    result.tag = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported lexord variant attribute"))
+                }
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = FieldAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("lexord")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("desc") {
+                    result.desc = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    result.default = Some(if meta.input.peek(Token![=]) {
+                        let value = meta.value()?;
+                        match value.peek(LitStr) {
+                            true => DefaultValue::Path(value.parse::<LitStr>()?.parse()?),
+                            false => DefaultValue::Path(value.parse()?),
+                        }
+                    } else {
+                        DefaultValue::Trait
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    let value = meta.value()?;
+                    result.with = Some(match value.peek(LitStr) {
+                        true => value.parse::<LitStr>()?.parse()?,
+                        false => value.parse()?,
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("position") {
+                    result.position = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("added_in") {
+                    result.added_in = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported lexord field attribute"))
+                }
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+fn parse_bound(input: ParseStream) -> syn::Result<Vec<WherePredicate>> {
+    let lit = input.parse::<LitStr>()?;
+    let predicates = lit.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
+
+pub fn discriminant(expr: &Expr) -> syn::Result<usize> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse(),
+        _ => Err(syn::Error::new(
+            expr.span(),
+            "lexord tags must be non-negative integer literals",
+        )),
+    }
+}
@@ -1,107 +1,1087 @@
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use syn::DeriveInput;
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{parse_quote, spanned::Spanned, DeriveInput};
+
+use crate::attr::{
+    self, BoundAttrs, CmpMode, ContainerAttrs, DefaultValue, FieldAttrs, IndexAttrs, VariantAttrs,
+};
 
 pub fn derive_lexord(input: DeriveInput) -> TokenStream {
+    derive(input, true)
+}
+
+pub fn derive_lexord_ser(input: DeriveInput) -> TokenStream {
+    derive(input, false)
+}
+
+fn derive(input: DeriveInput, decode: bool) -> TokenStream {
     let name = input.ident;
     let generics = input.generics;
-    let derives = match input.data {
-        syn::Data::Struct(data) => derive_struct(name, generics, data),
-        syn::Data::Enum(data) => derive_enum(name, generics, data),
-        _ => unimplemented!(),
-    };
+    let derives = ContainerAttrs::parse(&input.attrs).and_then(|attrs| match input.data {
+        syn::Data::Struct(data) => {
+            if let Some((_, lit)) = attrs.reserved.first() {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "reserved tags are only supported on enums",
+                ));
+            }
+            let indexes = attrs
+                .indexes
+                .iter()
+                .map(|index| index_key(&name, &input.vis, &generics, &data, &attrs, index, decode))
+                .collect::<syn::Result<TokenStream>>()?;
+            Ok((indexes, derive_struct(name, generics, data, attrs, decode)?))
+        }
+        syn::Data::Enum(data) => {
+            if let Some(span) = attrs.transparent {
+                return Err(syn::Error::new(
+                    span,
+                    "`transparent` is only supported on structs",
+                ));
+            }
+            if let Some(member) = attrs.order.as_ref().and_then(|order| order.first()) {
+                return Err(syn::Error::new(
+                    member.span(),
+                    "`order` is only supported on structs",
+                ));
+            }
+            if let Some(index) = attrs.indexes.first() {
+                return Err(syn::Error::new(
+                    index.name.span(),
+                    "`indexes` is only supported on structs",
+                ));
+            }
+            Ok((quote! {}, derive_enum(name, generics, data, attrs, decode)?))
+        }
+        syn::Data::Union(data) => Err(syn::Error::new(
+            data.union_token.span,
+            match decode {
+                true => "LexOrd cannot be derived for unions",
+                false => "LexOrdSer cannot be derived for unions",
+            },
+        )),
+    });
+    let (items, derives) = derives.unwrap_or_else(|error| (quote! {}, error.into_compile_error()));
     quote! {
+        #items
+
         const _: () = {
             #derives
         };
     }
 }
 
-fn derive_struct(name: syn::Ident, generics: syn::Generics, data: syn::DataStruct) -> TokenStream {
-    let (fields, types): (Vec<_>, Vec<_>) = data
-        .fields
-        .into_iter()
-        .enumerate()
-        .map(|(index, field)| {
-            let index = syn::Index::from(index);
-            let ident = match field.ident {
-                Some(ident) => quote! { #ident },
-                None => quote! { #index },
-            };
-            (ident, field.ty)
+struct Bounds {
+    ord: syn::Generics,
+    ser: syn::Generics,
+    de: syn::Generics,
+    de_ref: syn::Generics,
+}
+
+impl Bounds {
+    fn new(generics: &syn::Generics, fields: &[Field], attrs: &BoundAttrs, cmp: CmpMode) -> Self {
+        let mut ref_generics = generics.clone();
+        let lifetimes = generics.lifetimes().map(|param| &param.lifetime);
+        ref_generics
+            .params
+            .insert(0, parse_quote! { 'de: #( #lifetimes )+* });
+        // Borrowed fields such as `Cow<'a, str>` decode from any slice outliving `'a`.
+        let default = |field: &Field| {
+            field
+                .needs_default()
+                .then(|| quote! { ::core::default::Default })
+        };
+        Bounds {
+            ord: with_bounds(generics, fields, &attrs.ord, |field| {
+                field
+                    .needs_lexord()
+                    .then(|| match cmp {
+                        CmpMode::Total => quote! { ::core::cmp::Ord },
+                        _ => quote! { PartialOrd },
+                    })
+                    .into_iter()
+                    .collect()
+            }),
+            ser: with_bounds(generics, fields, &attrs.ser, |field| {
+                field
+                    .needs_lexord()
+                    .then(|| quote! { ::lexord::LexOrdSer })
+                    .into_iter()
+                    .collect()
+            }),
+            de: with_bounds(generics, fields, &attrs.de, |field| {
+                let de = field.needs_lexord().then(|| quote! { ::lexord::LexOrd });
+                de.into_iter().chain(default(field)).collect()
+            }),
+            de_ref: with_bounds(&ref_generics, fields, &attrs.de_ref, |field| {
+                // `Reverse<T>` only decodes from slices through `T: LexOrd`.
+                let de_ref = field.needs_lexord().then(|| match field.desc {
+                    true => quote! { ::lexord::LexOrd },
+                    false => quote! { ::lexord::LexOrdRef<'de> },
+                });
+                de_ref.into_iter().chain(default(field)).collect()
+            }),
+        }
+    }
+}
+
+fn with_bounds(
+    generics: &syn::Generics,
+    fields: &[Field],
+    bound: &Option<Vec<syn::WherePredicate>>,
+    infer: impl Fn(&Field) -> Vec<TokenStream>,
+) -> syn::Generics {
+    let mut generics = generics.clone();
+    let type_params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+    if let Some(bound) = bound {
+        where_clause.predicates.extend(bound.iter().cloned());
+        return generics;
+    }
+    let mut predicates: Vec<syn::WherePredicate> = vec![];
+    for param in &type_params {
+        for field in fields {
+            if !mentions_any(field.ty.to_token_stream(), &[param.to_string()]) {
+                continue;
+            }
+            for bound in infer(field) {
+                predicates.push(parse_quote! { #param: #bound });
+            }
+        }
+    }
+    // The codec handles the field, so it is the codec that must cover the type parameters.
+    let type_params: Vec<_> = type_params.iter().map(ToString::to_string).collect();
+    for field in fields {
+        let (Some(codec), false) = (&field.with, field.skip) else {
+            continue;
+        };
+        let ty = &field.ty;
+        if mentions_any(ty.to_token_stream(), &type_params) {
+            predicates.push(parse_quote! { #codec: ::lexord::LexOrdWith<#ty> });
+        }
+    }
+    for predicate in predicates {
+        if !where_clause
+            .predicates
+            .iter()
+            .any(|other| *other == predicate)
+        {
+            where_clause.predicates.push(predicate);
+        }
+    }
+    generics
+}
+
+fn mentions_any(tokens: TokenStream, idents: &[String]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => idents.contains(&ident.to_string()),
+        proc_macro2::TokenTree::Group(group) => mentions_any(group.stream(), idents),
+        _ => false,
+    })
+}
+
+#[derive(Clone)]
+struct Field {
+    member: TokenStream,
+    ty: syn::Type,
+    span: proc_macro2::Span,
+    desc: bool,
+    skip: bool,
+    default: Option<DefaultValue>,
+    added_in: Option<usize>,
+    with: Option<syn::Path>,
+    position: Option<usize>,
+}
+
+impl Field {
+    fn new(member: TokenStream, field: &syn::Field) -> syn::Result<Self> {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        let span = field.span();
+        if attrs.skip && attrs.desc {
+            return Err(syn::Error::new(
+                span,
+                "`skip` cannot be combined with `desc`",
+            ));
+        }
+        if attrs.skip && attrs.added_in.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`skip` cannot be combined with `added_in`",
+            ));
+        }
+        if attrs.skip && attrs.with.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`skip` cannot be combined with `with`",
+            ));
+        }
+        Ok(Field {
+            member,
+            ty: field.ty.clone(),
+            span,
+            desc: attrs.desc,
+            skip: attrs.skip,
+            default: attrs.default,
+            added_in: attrs.added_in,
+            with: attrs.with,
+            position: attrs.position,
         })
-        .unzip();
-    let (first_field, rest_fields) = fields.split_first().unwrap();
-    let (first_type, rest_types) = types.split_first().unwrap();
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    }
+
+    // Fields going through a codec put no requirements on their own type.
+    fn needs_lexord(&self) -> bool {
+        !self.skip && self.with.is_none()
+    }
+
+    fn needs_default(&self) -> bool {
+        (self.skip || self.is_trailing()) && !matches!(self.default, Some(DefaultValue::Path(_)))
+    }
+
+    fn is_trailing(&self) -> bool {
+        !self.skip && (self.default.is_some() || self.added_in.is_some())
+    }
+
+    fn ser_type(&self) -> TokenStream {
+        let ty = &self.ty;
+        match (self.desc, &self.with) {
+            (true, Some(codec)) => {
+                quote! { ::lexord::Reverse<::lexord::util::with::WithRef<'_, #codec, #ty>> }
+            }
+            (false, Some(codec)) => quote! { ::lexord::util::with::WithRef<'_, #codec, #ty> },
+            (true, None) => quote! { ::lexord::Reverse<&#ty> },
+            (false, None) => quote! { #ty },
+        }
+    }
+
+    fn ser_value(&self, value: TokenStream) -> TokenStream {
+        let value = match self.with {
+            Some(_) => quote! { ::lexord::util::with::WithRef::new(#value) },
+            None => value,
+        };
+        match (self.desc, &self.with) {
+            (true, _) => quote! { &::lexord::Reverse(#value) },
+            (false, Some(_)) => quote! { &#value },
+            (false, None) => value,
+        }
+    }
+
+    fn de_type(&self) -> TokenStream {
+        let ty = &self.ty;
+        let ty = match &self.with {
+            Some(codec) => quote! { ::lexord::util::with::With<#codec, #ty> },
+            None => quote! { #ty },
+        };
+        match self.desc {
+            true => quote! { ::lexord::Reverse<#ty> },
+            false => ty,
+        }
+    }
+
+    fn de_unwrap(&self) -> TokenStream {
+        let desc = self.desc.then(|| quote! { .0 });
+        let with = self.with.as_ref().map(|_| quote! { .0 });
+        quote! { #desc #with }
+    }
+
+    fn decode(&self, read: TokenStream, path: &TokenStream) -> TokenStream {
+        let unwrap = self.de_unwrap();
+        quote! { #read.map_err(#path)? #unwrap }
+    }
+
+    fn decode_trailing(&self, read: TokenStream, path: &TokenStream) -> TokenStream {
+        let unwrap = self.de_unwrap();
+        let default = self.default_value();
+        quote! {
+            match #read.map_err(#path)? {
+                ::core::option::Option::Some(value) => value #unwrap,
+                ::core::option::Option::None => #default,
+            }
+        }
+    }
+
+    fn default_value(&self) -> TokenStream {
+        match &self.default {
+            Some(DefaultValue::Path(path)) => quote! { #path() },
+            _ => quote! { ::core::default::Default::default() },
+        }
+    }
+
+    fn eq(&self, a: TokenStream, b: TokenStream) -> TokenStream {
+        let ty = &self.ty;
+        match &self.with {
+            Some(codec) => quote! {
+                <#codec as ::lexord::LexOrdWith<#ty>>::cmp(#a, #b) == ::core::cmp::Ordering::Equal
+            },
+            None => quote! { #a == #b },
+        }
+    }
+
+    fn cmp(&self, a: TokenStream, b: TokenStream, total: bool) -> TokenStream {
+        let ty = &self.ty;
+        let (a, b) = match self.desc {
+            true => (b, a),
+            false => (a, b),
+        };
+        let cmp = match (&self.with, total) {
+            (Some(codec), _) => quote_spanned! {self.span=>
+                <#codec as ::lexord::LexOrdWith<#ty>>::cmp(#a, #b)
+            },
+            (None, true) => quote_spanned! {self.span=>
+                <#ty as ::core::cmp::Ord>::cmp(#a, #b)
+            },
+            (None, false) => quote! { <#ty as PartialOrd>::partial_cmp(#a, #b)? },
+        };
+        let ordering = match total {
+            true => quote! { ordering },
+            false => quote! { Some(ordering) },
+        };
+        quote! {
+            match #cmp {
+                ::core::cmp::Ordering::Equal => {}
+                ordering => { return #ordering; }
+            }
+        }
+    }
+}
+
+fn ordering(ordering: TokenStream, total: bool) -> TokenStream {
+    match total {
+        true => quote! { ::core::cmp::Ordering::#ordering },
+        false => quote! { Some(::core::cmp::Ordering::#ordering) },
+    }
+}
+
+fn cmp_impls(
+    name: &syn::Ident,
+    bounds: &Bounds,
+    mode: CmpMode,
+    eq_body: TokenStream,
+    cmp_body: impl Fn(bool) -> TokenStream,
+) -> TokenStream {
+    let partial_cmp_body = match mode {
+        // The comparisons are written by hand, and `util::test::check_order` tests them.
+        CmpMode::None => return quote! {},
+        CmpMode::Partial => cmp_body(false),
+        CmpMode::Total => quote! { Some(::core::cmp::Ord::cmp(self, other)) },
+    };
+    let (impl_generics, ty_generics, where_clause) = bounds.ord.split_for_impl();
+    let total_impls = (mode == CmpMode::Total).then(|| {
+        let cmp_body = cmp_body(true);
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::cmp::Eq for #name #ty_generics #where_clause {}
+
+            #[automatically_derived]
+            impl #impl_generics ::core::cmp::Ord for #name #ty_generics #where_clause
+            {
+                fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                    #cmp_body
+                }
+            }
+        }
+    });
     quote! {
         #[automatically_derived]
         impl #impl_generics PartialEq for #name #ty_generics #where_clause
         {
             fn eq(&self, other: &Self) -> bool {
-                #( (self.#fields == other.#fields) && )* true
+                #eq_body
             }
         }
 
         #[automatically_derived]
         impl #impl_generics PartialOrd for #name #ty_generics #where_clause
         {
-            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-                #( match <#types as PartialOrd>::partial_cmp(&self.#fields, &other.#fields)? {
-                    std::cmp::Ordering::Equal => {}
-                    ordering => { return Some(ordering); }
-                } )*
-                Some(std::cmp::Ordering::Equal)
+            fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+                #partial_cmp_body
+            }
+        }
+
+        #total_impls
+    }
+}
+
+fn encodes_as_impls(name: &syn::Ident, bounds: &Bounds, attrs: &ContainerAttrs) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = bounds.ser.split_for_impl();
+    let encodes_as = &attrs.encodes_as;
+    quote! {
+        #(
+            #[automatically_derived]
+            impl #impl_generics ::lexord::EncodesAs<#encodes_as> for #name #ty_generics #where_clause {}
+        )*
+    }
+}
+
+// Sorts fields into the order they are encoded and compared in.
+fn encoding_order(fields: Vec<Field>, attrs: &ContainerAttrs) -> syn::Result<Vec<Field>> {
+    let positioned = fields.iter().find(|field| field.position.is_some());
+    let keys: Vec<Option<usize>> = match (&attrs.order, positioned) {
+        (None, None) => return Ok(fields),
+        (Some(_), Some(field)) => {
+            return Err(syn::Error::new(
+                field.span,
+                "`position` cannot be combined with the `order` container attribute",
+            ));
+        }
+        (Some(order), None) => {
+            let mut keys = vec![None; fields.len()];
+            for (key, member) in order.iter().enumerate() {
+                let name = member.to_token_stream().to_string();
+                let Some(index) = fields
+                    .iter()
+                    .position(|field| field.member.to_string() == name)
+                else {
+                    return Err(syn::Error::new(
+                        member.span(),
+                        format!("`order` names unknown field `{name}`"),
+                    ));
+                };
+                if fields[index].skip || keys[index].is_some() {
+                    return Err(syn::Error::new(
+                        member.span(),
+                        format!("field `{name}` is skipped or already ordered"),
+                    ));
+                }
+                keys[index] = Some(key);
             }
+            keys
+        }
+        (None, Some(_)) => fields.iter().map(|field| field.position).collect(),
+    };
+    for (index, field) in fields.iter().enumerate() {
+        if field.skip {
+            continue;
+        }
+        let Some(key) = keys[index] else {
+            return Err(syn::Error::new(
+                field.span,
+                match attrs.order {
+                    Some(_) => "field is missing from `order`",
+                    None => "`position` must be given on every field once it is given on one",
+                },
+            ));
+        };
+        if let Some(other) = (0..index).find(|&other| keys[other] == Some(key)) {
+            return Err(syn::Error::new(
+                field.span,
+                format!(
+                    "lexord position {key} is already used by field `{}`",
+                    fields[other].member
+                ),
+            ));
         }
+    }
+    // Skipped fields are not encoded, so their place does not matter.
+    let mut fields: Vec<_> = keys.into_iter().zip(fields).collect();
+    fields.sort_by_key(|(key, _)| key.unwrap_or(usize::MAX));
+    Ok(fields.into_iter().map(|(_, field)| field).collect())
+}
+
+fn check_trailing(fields: &[Field]) -> syn::Result<()> {
+    let mut added_in = None;
+    let mut trailing = false;
+    for field in fields.iter().filter(|field| !field.skip) {
+        if trailing && !field.is_trailing() {
+            return Err(syn::Error::new(
+                field.span,
+                "fields after a field with `default` or `added_in` need one as well",
+            ));
+        }
+        trailing = field.is_trailing();
+        if field.added_in.is_some() {
+            if field.added_in < added_in {
+                return Err(syn::Error::new(
+                    field.span,
+                    "`added_in` must not decrease across fields",
+                ));
+            }
+            added_in = field.added_in;
+        }
+    }
+    Ok(())
+}
+
+fn derive_struct(
+    name: syn::Ident,
+    generics: syn::Generics,
+    data: syn::DataStruct,
+    attrs: ContainerAttrs,
+    decode: bool,
+) -> syn::Result<TokenStream> {
+    let type_name = name.to_string();
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let index = syn::Index::from(index);
+            let member = match &field.ident {
+                Some(ident) => quote! { #ident },
+                None => quote! { #index },
+            };
+            Field::new(member, field)
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let fields = encoding_order(fields, &attrs)?;
+    if attrs.transparent.is_some() {
+        return derive_transparent(name, generics, fields, attrs, decode);
+    }
+    check_trailing(&fields)?;
+    let encoded: Vec<_> = fields.iter().filter(|field| !field.skip).collect();
+    let members: Vec<_> = fields.iter().map(|field| &field.member).collect();
+    let ser_types: Vec<_> = encoded.iter().map(|field| field.ser_type()).collect();
+    let ser_values: Vec<_> = encoded
+        .iter()
+        .map(|field| {
+            let member = &field.member;
+            field.ser_value(quote! { &self.#member })
+        })
+        .collect();
+    let de_types: Vec<_> = encoded.iter().map(|field| field.de_type()).collect();
+    let mut read_values = vec![];
+    let mut read_seq_values = vec![];
+    let mut slice_values = vec![];
+    let mut slice_seq_values = vec![];
+    for field in &fields {
+        if field.skip {
+            let default = field.default_value();
+            read_values.push(default.clone());
+            read_seq_values.push(default.clone());
+            slice_values.push(default.clone());
+            slice_seq_values.push(default);
+            continue;
+        }
+        let member = field.member.to_string();
+        let path = quote! {
+            |error: ::lexord::Error| error.with_field(#member).with_type(#type_name)
+        };
+        let de_type = field.de_type();
+        let read = quote! { <#de_type as ::lexord::LexOrd>::from_read(reader) };
+        let slice = quote! { <#de_type as ::lexord::LexOrdRef<'de>>::from_slice(slice) };
+        // In a sequence the struct is never at the end of the input, so every field is read.
+        if encoded
+            .first()
+            .is_some_and(|first| std::ptr::eq(field, *first))
+        {
+            read_seq_values.push(field.decode(
+                quote! { <#de_type as ::lexord::LexOrd>::from_read_seq(first, reader) },
+                &path,
+            ));
+            slice_seq_values.push(field.decode(
+                quote! { <#de_type as ::lexord::LexOrdRef<'de>>::from_slice_seq(slice) },
+                &path,
+            ));
+        } else {
+            read_seq_values.push(field.decode(read.clone(), &path));
+            slice_seq_values.push(field.decode(slice.clone(), &path));
+        }
+        if field.is_trailing() {
+            read_values.push(field.decode_trailing(
+                quote! { ::lexord::util::trailing::from_read::<#de_type>(reader) },
+                &path,
+            ));
+            slice_values.push(field.decode_trailing(
+                quote! { ::lexord::util::trailing::from_slice::<#de_type>(slice) },
+                &path,
+            ));
+        } else {
+            read_values.push(field.decode(read, &path));
+            slice_values.push(field.decode(slice, &path));
+        }
+    }
+    // Without encoded fields the value is empty and the default sequence encoding applies.
+    let (ser_seq, read_seq, slice_seq) = match (ser_types.split_first(), ser_values.split_first()) {
+        (Some((first_ser_type, rest_ser_types)), Some((first_ser_value, rest_ser_values))) => (
+            quote! {
+                fn to_write_seq(&self, writer: &mut impl ::lexord::io::Sink) -> ::lexord::Result {
+                    <#first_ser_type as ::lexord::LexOrdSer>::to_write_seq(#first_ser_value, writer)?;
+                    #( <#rest_ser_types as ::lexord::LexOrdSer>::to_write(#rest_ser_values, writer)?; )*
+                    Ok(())
+                }
+                fn encoded_len_seq(&self) -> usize {
+                    <#first_ser_type as ::lexord::LexOrdSer>::encoded_len_seq(#first_ser_value)
+                        #( + <#rest_ser_types as ::lexord::LexOrdSer>::encoded_len(#rest_ser_values) )*
+                }
+            },
+            quote! {
+                fn from_read_seq(first: u8, reader: &mut impl ::lexord::io::Source) -> ::lexord::Result<Self> {
+                    ::lexord::util::nested(reader, |reader| {
+                        Ok(#name {
+                            #( #members: #read_seq_values, )*
+                        })
+                    })
+                    .map_err(|error| error.with_type(#type_name))
+                }
+            },
+            quote! {
+                fn from_slice_seq(slice: &mut ::lexord::io::SliceReader<'de>) -> ::lexord::Result<Self> {
+                    ::lexord::util::nested(slice, |slice| {
+                        Ok(#name {
+                            #( #members: #slice_seq_values, )*
+                        })
+                    })
+                    .map_err(|error| error.with_type(#type_name))
+                }
+            },
+        ),
+        _ => (quote! {}, quote! {}, quote! {}),
+    };
+    let bounds = Bounds::new(&generics, &fields, &attrs.bound, attrs.cmp);
+    let cmp_impls = cmp_impls(
+        &name,
+        &bounds,
+        attrs.cmp,
+        {
+            let eqs = encoded.iter().map(|field| {
+                let member = &field.member;
+                field.eq(quote! { &self.#member }, quote! { &other.#member })
+            });
+            quote! { #( (#eqs) && )* true }
+        },
+        |total| {
+            let cmps = encoded.iter().map(|field| {
+                let member = &field.member;
+                field.cmp(quote! { &self.#member }, quote! { &other.#member }, total)
+            });
+            let equal = ordering(quote! { Equal }, total);
+            quote! { #( #cmps )* #equal }
+        },
+    );
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (impl_generics, _, where_clause) = bounds.ser.split_for_impl();
+    let (de_impl_generics, _, de_where_clause) = bounds.de.split_for_impl();
+    let (ref_impl_generics, _, ref_where_clause) = bounds.de_ref.split_for_impl();
+    let prefixes = (0..=encoded.len().min(12)).map(|prefix_size| {
+        let prefix_types = &de_types[..prefix_size];
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::lexord::PrefixOf<#name #ty_generics> for ( #( #prefix_types, )* )
+                #where_clause {}
+        }
+    });
+    let type_params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect();
+    let partial_prefixes = (1..=encoded.len().min(12)).filter_map(|prefix_size| {
+        let prefix_types = &de_types[..prefix_size - 1];
+        let partial_field = encoded[prefix_size - 1];
+        let partial_type = &partial_field.ty;
+        // A field mentioning a type parameter could itself be a partial prefix type,
+        // which would make the impls below overlap.
+        if partial_field.desc
+            || partial_field.with.is_some()
+            || mentions_any(partial_type.to_token_stream(), &type_params)
+        {
+            return None;
+        }
+        let partial = |wrapper: TokenStream| {
+            let mut partial_generics = bounds.ser.clone();
+            partial_generics
+                .params
+                .insert(0, parse_quote! { '__lexord_prefix });
+            partial_generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! {
+                    #wrapper<'__lexord_prefix>: ::lexord::PrefixOf<#partial_type>
+                });
+            let (impl_generics, _, where_clause) = partial_generics.split_for_impl();
+            quote! {
+                #[automatically_derived]
+                impl #impl_generics ::lexord::PrefixOf<#name #ty_generics>
+                    for ( #( #prefix_types, )* #wrapper<'__lexord_prefix>, )
+                    #where_clause {}
+            }
+        };
+        let str_prefix = partial(quote! { ::lexord::StrPrefix });
+        let bytes_prefix = partial(quote! { ::lexord::BytesPrefix });
+        Some(quote! { #str_prefix #bytes_prefix })
+    });
+    let de_impls = decode.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #de_impl_generics ::lexord::LexOrd for #name #ty_generics #de_where_clause
+            {
+                fn from_read(reader: &mut impl ::lexord::io::Source) -> ::lexord::Result<Self> {
+                    ::lexord::util::nested(reader, |reader| {
+                        Ok(#name {
+                            #( #members: #read_values, )*
+                        })
+                    })
+                    .map_err(|error| error.with_type(#type_name))
+                }
+                #read_seq
+            }
+
+            #[automatically_derived]
+            impl #ref_impl_generics ::lexord::LexOrdRef<'de> for #name #ty_generics #ref_where_clause
+            {
+                fn from_slice(slice: &mut ::lexord::io::SliceReader<'de>) -> ::lexord::Result<Self> {
+                    ::lexord::util::nested(slice, |slice| {
+                        Ok(#name {
+                            #( #members: #slice_values, )*
+                        })
+                    })
+                    .map_err(|error| error.with_type(#type_name))
+                }
+                #slice_seq
+            }
+        }
+    });
+    let encodes_as = encodes_as_impls(&name, &bounds, &attrs);
+    Ok(quote! {
+        #cmp_impls
+
+        #( #prefixes )*
+        #( #partial_prefixes )*
+        #encodes_as
 
         #[automatically_derived]
         impl #impl_generics ::lexord::LexOrdSer for #name #ty_generics #where_clause
         {
-            fn to_write(&self, writer: &mut impl std::io::Write) -> ::lexord::Result {
-                #( <#types as ::lexord::LexOrdSer>::to_write(&self.#fields, writer)?; )*
+            const MAX_ENCODED_LEN: Option<usize> = {
+                let len = Some(0);
+                #( let len = ::lexord::util::len::sum(
+                    len,
+                    <#ser_types as ::lexord::LexOrdSer>::MAX_ENCODED_LEN,
+                ); )*
+                len
+            };
+
+            fn to_write(&self, writer: &mut impl ::lexord::io::Sink) -> ::lexord::Result {
+                #( <#ser_types as ::lexord::LexOrdSer>::to_write(#ser_values, writer)?; )*
                 Ok(())
             }
-            fn to_write_seq(&self, writer: &mut impl std::io::Write) -> ::lexord::Result {
-                <#first_type as ::lexord::LexOrdSer>::to_write_seq(&self.#first_field, writer)?;
-                #( <#rest_types as ::lexord::LexOrdSer>::to_write(&self.#rest_fields, writer)?; )*
-                Ok(())
+            fn encoded_len(&self) -> usize {
+                0 #( + <#ser_types as ::lexord::LexOrdSer>::encoded_len(#ser_values) )*
+            }
+            #ser_seq
+        }
+
+        #de_impls
+    })
+}
+
+fn derive_transparent(
+    name: syn::Ident,
+    generics: syn::Generics,
+    fields: Vec<Field>,
+    attrs: ContainerAttrs,
+    decode: bool,
+) -> syn::Result<TokenStream> {
+    let mut encoded = fields.iter().filter(|field| !field.skip);
+    let (Some(field), None) = (encoded.next(), encoded.next()) else {
+        return Err(syn::Error::new(
+            name.span(),
+            "`transparent` requires exactly one field that is not skipped",
+        ));
+    };
+    if field.desc || field.with.is_some() || field.is_trailing() {
+        return Err(syn::Error::new(
+            field.span,
+            "the field of a `transparent` struct cannot use `desc`, `with`, `default` or `added_in`",
+        ));
+    }
+    let (member, ty) = (&field.member, &field.ty);
+    let members: Vec<_> = fields.iter().map(|field| &field.member).collect();
+    let construct = |value: TokenStream| {
+        let values = fields.iter().map(|field| match field.skip {
+            true => field.default_value(),
+            false => value.clone(),
+        });
+        quote! { #name { #( #members: #values, )* } }
+    };
+    let bounds = Bounds::new(&generics, &fields, &attrs.bound, attrs.cmp);
+    let cmp_impls = cmp_impls(
+        &name,
+        &bounds,
+        attrs.cmp,
+        quote! { self.#member == other.#member },
+        |total| match total {
+            true => quote_spanned! {field.span=>
+                <#ty as ::core::cmp::Ord>::cmp(&self.#member, &other.#member)
+            },
+            false => quote! { <#ty as PartialOrd>::partial_cmp(&self.#member, &other.#member) },
+        },
+    );
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (impl_generics, _, where_clause) = bounds.ser.split_for_impl();
+    let (de_impl_generics, _, de_where_clause) = bounds.de.split_for_impl();
+    let (ref_impl_generics, _, ref_where_clause) = bounds.de_ref.split_for_impl();
+    // Every prefix of the field is a prefix of the newtype. A blanket impl over
+    // `P: PrefixOf<#ty>` would break the orphan rules, so each prefix shape is forwarded.
+    let forward = |prefix: TokenStream, params: Vec<syn::GenericParam>| {
+        let mut prefix_generics = bounds.ser.clone();
+        for param in params.into_iter().rev() {
+            prefix_generics.params.insert(0, param);
+        }
+        prefix_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote! { #prefix: ::lexord::PrefixOf<#ty> });
+        let (impl_generics, _, where_clause) = prefix_generics.split_for_impl();
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::lexord::PrefixOf<#name #ty_generics> for #prefix #where_clause {}
+        }
+    };
+    let mut prefixes = vec![
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::lexord::PrefixOf<#name #ty_generics> for () #where_clause {}
+        },
+        forward(
+            quote! { ::lexord::StrPrefix<'__lexord_prefix> },
+            vec![parse_quote! { '__lexord_prefix }],
+        ),
+        forward(
+            quote! { ::lexord::BytesPrefix<'__lexord_prefix> },
+            vec![parse_quote! { '__lexord_prefix }],
+        ),
+    ];
+    for size in 1..=12 {
+        let params: Vec<_> = (0..size).map(|index| format_ident!("__P{index}")).collect();
+        prefixes.push(forward(
+            quote! { ( #( #params, )* ) },
+            params.iter().map(|param| parse_quote! { #param }).collect(),
+        ));
+    }
+    // The field itself is the full prefix, unless it could be a tuple forwarded above.
+    let type_params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect();
+    if !matches!(ty, syn::Type::Tuple(_)) && !mentions_any(ty.to_token_stream(), &type_params) {
+        prefixes.push(quote! {
+            #[automatically_derived]
+            impl #impl_generics ::lexord::PrefixOf<#name #ty_generics> for #ty #where_clause {}
+        });
+    }
+    let read = construct(quote! { <#ty as ::lexord::LexOrd>::from_read(reader)? });
+    let read_seq = construct(quote! { <#ty as ::lexord::LexOrd>::from_read_seq(first, reader)? });
+    let slice = construct(quote! { <#ty as ::lexord::LexOrdRef<'de>>::from_slice(slice)? });
+    let slice_seq = construct(quote! { <#ty as ::lexord::LexOrdRef<'de>>::from_slice_seq(slice)? });
+    let de_impls = decode.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #de_impl_generics ::lexord::LexOrd for #name #ty_generics #de_where_clause
+            {
+                fn from_read(reader: &mut impl ::lexord::io::Source) -> ::lexord::Result<Self> {
+                    Ok(#read)
+                }
+                fn from_read_seq(first: u8, reader: &mut impl ::lexord::io::Source) -> ::lexord::Result<Self> {
+                    Ok(#read_seq)
+                }
+            }
+
+            #[automatically_derived]
+            impl #ref_impl_generics ::lexord::LexOrdRef<'de> for #name #ty_generics #ref_where_clause
+            {
+                fn from_slice(slice: &mut ::lexord::io::SliceReader<'de>) -> ::lexord::Result<Self> {
+                    Ok(#slice)
+                }
+                fn from_slice_seq(slice: &mut ::lexord::io::SliceReader<'de>) -> ::lexord::Result<Self> {
+                    Ok(#slice_seq)
+                }
             }
         }
+    });
+    let encodes_as = encodes_as_impls(&name, &bounds, &attrs);
+    Ok(quote! {
+        #cmp_impls
+
+        #( #prefixes )*
+        #encodes_as
 
         #[automatically_derived]
-        impl #impl_generics ::lexord::LexOrd for #name #ty_generics #where_clause
+        impl #impl_generics ::lexord::LexOrdSer for #name #ty_generics #where_clause
         {
-            fn from_read(reader: &mut impl std::io::Read) -> ::lexord::Result<Self> {
-                Ok(#name {
-                    #( #fields: <#types as ::lexord::LexOrd>::from_read(reader)?, )*
-                })
+            const MAX_ENCODED_LEN: Option<usize> = <#ty as ::lexord::LexOrdSer>::MAX_ENCODED_LEN;
+
+            fn to_write(&self, writer: &mut impl ::lexord::io::Sink) -> ::lexord::Result {
+                <#ty as ::lexord::LexOrdSer>::to_write(&self.#member, writer)
+            }
+            fn to_write_seq(&self, writer: &mut impl ::lexord::io::Sink) -> ::lexord::Result {
+                <#ty as ::lexord::LexOrdSer>::to_write_seq(&self.#member, writer)
+            }
+            fn encoded_len(&self) -> usize {
+                <#ty as ::lexord::LexOrdSer>::encoded_len(&self.#member)
             }
-            fn from_read_seq(first: u8, reader: &mut impl std::io::Read) -> ::lexord::Result<Self> {
-                Ok(#name {
-                    #first_field: <#first_type as ::lexord::LexOrd>::from_read_seq(first, reader)?,
-                    #( #rest_fields: <#rest_types as ::lexord::LexOrd>::from_read(reader)?, )*
+            fn encoded_len_seq(&self) -> usize {
+                <#ty as ::lexord::LexOrdSer>::encoded_len_seq(&self.#member)
+            }
+        }
+
+        #de_impls
+    })
+}
+
+// Generates the key type of a secondary index and the method extracting it. The key
+// fields keep their `desc` and `with` attributes, so they encode as in the struct.
+fn index_key(
+    name: &syn::Ident,
+    vis: &syn::Visibility,
+    generics: &syn::Generics,
+    data: &syn::DataStruct,
+    attrs: &ContainerAttrs,
+    index: &IndexAttrs,
+    decode: bool,
+) -> syn::Result<TokenStream> {
+    let index_name = index.name.to_string();
+    let method = format_ident!("{index_name}_key", span = index.name.span());
+    let key_name = format_ident!(
+        "{}Key",
+        index_name
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map_or(String::new(), |first| {
+                    first.to_uppercase().chain(chars).collect()
                 })
+            })
+            .collect::<String>(),
+        span = index.name.span()
+    );
+    if !generics.params.is_empty() {
+        return Err(syn::Error::new(
+            index.name.span(),
+            "`indexes` is not supported on generic structs",
+        ));
+    }
+    let named = matches!(data.fields, syn::Fields::Named(_));
+    let mut key_fields = vec![];
+    let mut values = vec![];
+    for (position, member) in index.fields.iter().enumerate() {
+        let member_name = member.to_token_stream().to_string();
+        let Some(field) = data.fields.members().position(|other| other == *member) else {
+            return Err(syn::Error::new(
+                member.span(),
+                format!("`indexes` names unknown field `{member_name}`"),
+            ));
+        };
+        let syn_field = data.fields.iter().nth(field).expect("member has a field");
+        let field = Field::new(member.to_token_stream(), syn_field)?;
+        if field.skip || index.fields[..position].contains(member) {
+            return Err(syn::Error::new(
+                member.span(),
+                format!("field `{member_name}` is skipped or already in the index"),
+            ));
+        }
+        let ty = &field.ty;
+        let desc = field.desc.then(|| quote! { #[lexord(desc)] });
+        let with = field
+            .with
+            .as_ref()
+            .map(|with| quote! { #[lexord(with = #with)] });
+        let key_member = match named {
+            true => quote! { #member: },
+            false => quote! {},
+        };
+        key_fields.push(quote! { #desc #with #vis #key_member #ty });
+        // Keys own their fields, so building one clones them, and the error for a field
+        // that is not `Clone` points at that field.
+        values.push(quote_spanned! {field.span=> ::core::clone::Clone::clone(&self.#member) });
+    }
+    let key_members: Vec<_> = match named {
+        true => index.fields.iter().map(ToTokens::to_token_stream).collect(),
+        false => (0..index.fields.len())
+            .map(|index| syn::Index::from(index).to_token_stream())
+            .collect(),
+    };
+    let derive = match decode {
+        true => quote! { ::lexord::LexOrd },
+        false => quote! { ::lexord::LexOrdSer },
+    };
+    let ord = (attrs.cmp == CmpMode::Total).then(|| quote! { #[lexord(ord)] });
+    let body = match named {
+        true => quote! { { #( #key_fields, )* } },
+        false => quote! { ( #( #key_fields, )* ); },
+    };
+    let derives = &index.derives;
+    let doc = format!("Builds the `{key_name}` of this value from clones of its indexed fields.");
+    Ok(quote! {
+        #[derive(#derive #( , #derives )*)]
+        #ord
+        #vis struct #key_name #body
+
+        impl #name {
+            #[doc = #doc]
+            #vis fn #method(&self) -> #key_name {
+                #key_name {
+                    #( #key_members: #values, )*
+                }
             }
         }
+    })
+}
+
+fn variant_tags(data: &syn::DataEnum, attrs: &ContainerAttrs) -> syn::Result<Vec<usize>> {
+    let mut tags: Vec<usize> = vec![];
+    for variant in &data.variants {
+        let tag = match (
+            VariantAttrs::parse(&variant.attrs)?.tag,
+            &variant.discriminant,
+        ) {
+            (Some(tag), _) => tag,
+            (None, Some((_, expr))) => attr::discriminant(expr)?,
+            (None, None) => match tags.last() {
+                Some(tag) => tag.checked_add(1).ok_or_else(|| {
+                    syn::Error::new(variant.ident.span(), "lexord tag overflows usize")
+                })?,
+                None => 0,
+            },
+        };
+        if let Some(index) = tags.iter().position(|&other| other == tag) {
+            let other = &data.variants[index].ident;
+            return Err(syn::Error::new(
+                variant.ident.span(),
+                format!("lexord tag {tag} is already used by variant `{other}`"),
+            ));
+        }
+        if attrs.reserved.iter().any(|&(reserved, _)| reserved == tag) {
+            return Err(syn::Error::new(
+                variant.ident.span(),
+                format!("lexord tag {tag} is reserved"),
+            ));
+        }
+        tags.push(tag);
     }
+    Ok(tags)
 }
 
-fn derive_enum(name: syn::Ident, generics: syn::Generics, data: syn::DataEnum) -> TokenStream {
+fn derive_enum(
+    name: syn::Ident,
+    generics: syn::Generics,
+    data: syn::DataEnum,
+    attrs: ContainerAttrs,
+    decode: bool,
+) -> syn::Result<TokenStream> {
+    let tags = variant_tags(&data, &attrs)?;
     let mut eq_hands = vec![];
     let mut cmp_hands = vec![];
     let mut write_hands = vec![];
+    let mut len_hands = vec![];
+    let mut max_lens = vec![];
     let mut read_hands = vec![];
+    let mut slice_hands = vec![];
+    let mut bound_fields = vec![];
+    let type_name = name.to_string();
 
-    for (var_index, variant) in data.variants.iter().enumerate() {
-        assert!(variant.discriminant.is_none());
+    for (&var_index, variant) in tags.iter().zip(&data.variants) {
         let var_name = &variant.ident;
-        let mut field_types = vec![];
+        let var_name_str = var_name.to_string();
+        let mut fields = vec![];
         let mut field_names = vec![];
         let mut a_field_names = vec![];
         let mut b_field_names = vec![];
+        let mut all_field_names = vec![];
+        let mut read_values = vec![];
+        let mut slice_values = vec![];
         for (index, field) in variant.fields.iter().enumerate() {
-            field_types.push(&field.ty);
             let field_name = match &field.ident {
                 Some(ident) => quote! { #ident },
                 None => {
@@ -109,107 +1089,249 @@ fn derive_enum(name: syn::Ident, generics: syn::Generics, data: syn::DataEnum) -
                     quote! { #index }
                 }
             };
-            field_names.push(quote!(#field_name));
+            let field = Field::new(field_name.clone(), field)?;
+            if field.is_trailing() {
+                return Err(syn::Error::new(
+                    field.span,
+                    "`default` and `added_in` are only supported on struct fields",
+                ));
+            }
+            if field.position.is_some() {
+                return Err(syn::Error::new(
+                    field.span,
+                    "`position` is only supported on struct fields",
+                ));
+            }
+            bound_fields.push(field.clone());
+            all_field_names.push(field_name.clone());
+            if field.skip {
+                read_values.push(field.default_value());
+                slice_values.push(field.default_value());
+                continue;
+            }
+            let field_name_str = field_name.to_string();
+            let path = quote! {
+                |error: ::lexord::Error| error
+                    .with_field(#field_name_str)
+                    .with_field(#var_name_str)
+                    .with_type(#type_name)
+            };
+            let de_type = field.de_type();
+            read_values.push(field.decode(
+                quote! { <#de_type as ::lexord::LexOrd>::from_read(reader) },
+                &path,
+            ));
+            slice_values.push(field.decode(
+                quote! { <#de_type as ::lexord::LexOrdRef<'de>>::from_slice(slice) },
+                &path,
+            ));
             a_field_names.push(format_ident!("a_{field_name}"));
             b_field_names.push(format_ident!("b_{field_name}"));
+            fields.push(field);
+            field_names.push(field_name);
         }
+        let ser_types: Vec<_> = fields.iter().map(Field::ser_type).collect();
+        let ser_values: Vec<_> = fields
+            .iter()
+            .zip(&a_field_names)
+            .map(|(field, a)| field.ser_value(quote! { #a }))
+            .collect();
+        let eqs = fields
+            .iter()
+            .zip(a_field_names.iter().zip(&b_field_names))
+            .map(|(field, (a, b))| field.eq(quote! { #a }, quote! { #b }));
         eq_hands.push(quote! {
             (
-                #name::#var_name{ #( #field_names: #a_field_names, )* },
-                #name::#var_name{ #( #field_names: #b_field_names, )* }
+                #name::#var_name{ #( #field_names: #a_field_names, )* .. },
+                #name::#var_name{ #( #field_names: #b_field_names, )* .. }
             ) => {
-                #( #a_field_names == #b_field_names && )* true
+                #( (#eqs) && )* true
             }
         });
-        cmp_hands.push(quote! {
-            (
-                #name::#var_name{ #( #field_names: #a_field_names, )* },
-                #name::#var_name{ #( #field_names: #b_field_names, )* }
-            ) => {
-                #(
-                    match <#field_types as PartialOrd>::partial_cmp(
-                        &#a_field_names,
-                        &#b_field_names
-                    )? {
-                        std::cmp::Ordering::Equal => {}
-                        ordering => { return Some(ordering); }
-                    };
-                )*
-                return Some(std::cmp::Ordering::Equal);
-            }
-            (_, #name::#var_name{ .. }) => Some(std::cmp::Ordering::Greater),
-            (#name::#var_name{ .. }, _) => Some(std::cmp::Ordering::Less),
-        });
+        let cmp_hand = |total: bool| {
+            let cmps = fields
+                .iter()
+                .zip(a_field_names.iter().zip(&b_field_names))
+                .map(|(field, (a, b))| field.cmp(quote! { #a }, quote! { #b }, total));
+            let (equal, greater, less) = (
+                ordering(quote! { Equal }, total),
+                ordering(quote! { Greater }, total),
+                ordering(quote! { Less }, total),
+            );
+            quote! {
+                (
+                    #name::#var_name{ #( #field_names: #a_field_names, )* .. },
+                    #name::#var_name{ #( #field_names: #b_field_names, )* .. }
+                ) => {
+                    #( #cmps )*
+                    #equal
+                }
+                (_, #name::#var_name{ .. }) => #greater,
+                (#name::#var_name{ .. }, _) => #less,
+            }
+        };
+        cmp_hands.push((var_index, cmp_hand(false), cmp_hand(true)));
         write_hands.push(quote! {
-            #name::#var_name{ #( #field_names: #a_field_names, )* } => {
+            #name::#var_name{ #( #field_names: #a_field_names, )* .. } => {
                 <usize as ::lexord::LexOrdSer>::to_write(&#var_index, writer)?;
-                #( <#field_types as ::lexord::LexOrdSer>::to_write(&#a_field_names, writer)?; )*
+                #( <#ser_types as ::lexord::LexOrdSer>::to_write(#ser_values, writer)?; )*
             }
         });
-        read_hands.push(quote! {
-            #var_index => {
-                #name::#var_name{ #(
-                    #field_names: <#field_types as ::lexord::LexOrd>::from_read(reader)?,
-                )* }
+        len_hands.push(quote! {
+            #name::#var_name{ #( #field_names: #a_field_names, )* .. } => {
+                <usize as ::lexord::LexOrdSer>::encoded_len(&#var_index)
+                    #( + <#ser_types as ::lexord::LexOrdSer>::encoded_len(#ser_values) )*
+            }
+        });
+        max_lens.push(quote! {
+            {
+                let len = Some(::lexord::util::len::uint_len(#var_index as u128));
+                #( let len = ::lexord::util::len::sum(
+                    len,
+                    <#ser_types as ::lexord::LexOrdSer>::MAX_ENCODED_LEN,
+                ); )*
+                len
             }
         });
+        read_hands.push(quote! {
+            #var_index => Ok(#name::#var_name{ #( #all_field_names: #read_values, )* }),
+        });
+        slice_hands.push(quote! {
+            #var_index => Ok(#name::#var_name{ #( #all_field_names: #slice_values, )* }),
+        });
     }
 
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    quote! {
-        #[automatically_derived]
-        impl #impl_generics PartialEq for #name #ty_generics #where_clause
-        {
-            fn eq(&self, other: &Self) -> bool {
+    // Variants compare in tag order so that `PartialOrd` agrees with the encoding.
+    cmp_hands.sort_by_key(|&(var_index, _, _)| var_index);
+
+    let unknown_variant = quote! {
+        var_index => Err(::lexord::Error::new(
+            ::lexord::ErrorKind::UnknownVariant(var_index)
+        ).with_type(#type_name)),
+    };
+    let read_variant = quote! {
+        match var_index {
+            #( #read_hands )*
+            #unknown_variant
+        }
+    };
+
+    // A zero-variant enum has no values, so matching on `*self` needs no arms.
+    let (eq_body, write_body, len_body) = match data.variants.is_empty() {
+        true => (
+            quote! { match *self {} },
+            quote! { match *self {} },
+            quote! { match *self {} },
+        ),
+        false => (
+            quote! {
                 match (self, other) {
                     #( #eq_hands )*
                     _ => false
                 }
+            },
+            quote! {
+                match self {
+                    #( #write_hands )*
+                }
+                Ok(())
+            },
+            quote! {
+                match self {
+                    #( #len_hands )*
+                }
+            },
+        ),
+    };
+    let cmp_body = |total: bool| {
+        if data.variants.is_empty() {
+            return quote! { match *self {} };
+        }
+        let hands = cmp_hands.iter().map(|(_, partial, full)| match total {
+            true => full,
+            false => partial,
+        });
+        quote! {
+            match (self, other) {
+                #( #hands )*
             }
         }
+    };
 
-        #[automatically_derived]
-        impl #impl_generics PartialOrd for #name #ty_generics #where_clause
-        {
-            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-                match (self, other) {
-                    #( #cmp_hands )*
+    let bounds = Bounds::new(&generics, &bound_fields, &attrs.bound, attrs.cmp);
+    let cmp_impls = cmp_impls(&name, &bounds, attrs.cmp, eq_body, cmp_body);
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (impl_generics, _, where_clause) = bounds.ser.split_for_impl();
+    let (de_impl_generics, _, de_where_clause) = bounds.de.split_for_impl();
+    let (ref_impl_generics, _, ref_where_clause) = bounds.de_ref.split_for_impl();
+    let de_impls = decode.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #de_impl_generics ::lexord::LexOrd for #name #ty_generics #de_where_clause
+            {
+                fn from_read(reader: &mut impl ::lexord::io::Source) -> ::lexord::Result<Self> {
+                    ::lexord::util::nested(reader, |reader| {
+                        let var_index = <usize as ::lexord::LexOrd>::from_read(reader)?;
+                        #read_variant
+                    })
+                    .map_err(|error| error.with_type(#type_name))
+                }
+                fn from_read_seq(first: u8, reader: &mut impl ::lexord::io::Source) -> ::lexord::Result<Self> {
+                    ::lexord::util::nested(reader, |reader| {
+                        let var_index = <usize as ::lexord::LexOrd>::from_read_seq(first, reader)?;
+                        #read_variant
+                    })
+                    .map_err(|error| error.with_type(#type_name))
                 }
+            }
 
+            #[automatically_derived]
+            impl #ref_impl_generics ::lexord::LexOrdRef<'de> for #name #ty_generics #ref_where_clause
+            {
+                fn from_slice(slice: &mut ::lexord::io::SliceReader<'de>) -> ::lexord::Result<Self> {
+                    ::lexord::util::nested(slice, |slice| {
+                        let var_index = <usize as ::lexord::LexOrdRef<'de>>::from_slice(slice)?;
+                        match var_index {
+                            #( #slice_hands )*
+                            #unknown_variant
+                        }
+                    })
+                    .map_err(|error| error.with_type(#type_name))
+                }
+                fn from_slice_seq(slice: &mut ::lexord::io::SliceReader<'de>) -> ::lexord::Result<Self> {
+                    Self::from_slice(slice)
+                }
             }
         }
+    });
+    let encodes_as = encodes_as_impls(&name, &bounds, &attrs);
+    Ok(quote! {
+        #cmp_impls
+        #encodes_as
 
         #[automatically_derived]
         impl #impl_generics ::lexord::LexOrdSer for #name #ty_generics #where_clause
         {
-            fn to_write(&self, writer: &mut impl std::io::Write) -> ::lexord::Result {
-                match self {
-                    #( #write_hands )*
-                }
-                Ok(())
+            const MAX_ENCODED_LEN: Option<usize> = {
+                let len = Some(0);
+                #( let len = ::lexord::util::len::max(len, #max_lens); )*
+                len
+            };
+
+            fn to_write(&self, writer: &mut impl ::lexord::io::Sink) -> ::lexord::Result {
+                #write_body
             }
-            fn to_write_seq(&self, writer: &mut impl std::io::Write) -> ::lexord::Result {
+            fn to_write_seq(&self, writer: &mut impl ::lexord::io::Sink) -> ::lexord::Result {
                 self.to_write(writer)
             }
-        }
-
-        #[automatically_derived]
-        impl #impl_generics ::lexord::LexOrd for #name #ty_generics #where_clause
-        {
-            fn from_read(reader: &mut impl std::io::Read) -> ::lexord::Result<Self> {
-                Ok(match <usize as ::lexord::LexOrd>::from_read(reader)? {
-                    #( #read_hands )*
-                    var_index => {
-                        Err(::lexord::Error::Parse(
-                            format!("Unexpected enum variant: {var_index}")
-                        ))?;
-                        unreachable!()
-                    }
-                })
+            fn encoded_len(&self) -> usize {
+                #len_body
             }
-            fn from_read_seq(first: u8, reader: &mut impl std::io::Read) -> ::lexord::Result<Self> {
-                Self::from_read(&mut <&[u8] as std::io::Read>::chain(&[first], reader))
+            fn encoded_len_seq(&self) -> usize {
+                self.encoded_len()
             }
         }
-    }
+
+        #de_impls
+    })
 }
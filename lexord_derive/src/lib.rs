@@ -1,3 +1,4 @@
+mod attr;
 mod derive;
 mod tuples;
 
@@ -6,9 +7,16 @@ pub fn gen_lexord_for_tuples(_input: proc_macro::TokenStream) -> proc_macro::Tok
     proc_macro::TokenStream::from(tuples::gen_lexord_for_tuples())
 }
 
-#[proc_macro_derive(LexOrd)]
+#[proc_macro_derive(LexOrd, attributes(lexord))]
 pub fn derive_lexord(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     proc_macro::TokenStream::from(derive::derive_lexord(syn::parse_macro_input!(
         input as syn::DeriveInput
     )))
 }
+
+#[proc_macro_derive(LexOrdSer, attributes(lexord))]
+pub fn derive_lexord_ser(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    proc_macro::TokenStream::from(derive::derive_lexord_ser(syn::parse_macro_input!(
+        input as syn::DeriveInput
+    )))
+}
@@ -13,28 +13,89 @@ pub fn gen_lexord_for_tuples() -> TokenStream {
         let types_no_first: Vec<_> = (1..tuple_size)
             .map(|index| format_ident!("T{}", index))
             .collect();
+        let names: Vec<_> = (0..tuple_size).map(|index| index.to_string()).collect();
+        let first_name = names.first().unwrap();
+        let names_no_first = &names[1..];
+        let prefixes = (0..=tuple_size).map(|prefix_size| {
+            let prefix_types = &types[..prefix_size];
+            quote! {
+                impl<#( #types: LexOrdSer ),*> PrefixOf<( #( #types, )* )> for ( #( #prefix_types, )* ) {}
+            }
+        });
+        let partial_prefixes = (1..=tuple_size).map(|prefix_size| {
+            let prefix_types = &types[..prefix_size - 1];
+            let partial_type = &types[prefix_size - 1];
+            quote! {
+                impl<'p, #( #types: LexOrdSer ),*> PrefixOf<( #( #types, )* )>
+                    for ( #( #prefix_types, )* StrPrefix<'p>, )
+                    where StrPrefix<'p>: PrefixOf<#partial_type> {}
+                impl<'p, #( #types: LexOrdSer ),*> PrefixOf<( #( #types, )* )>
+                    for ( #( #prefix_types, )* BytesPrefix<'p>, )
+                    where BytesPrefix<'p>: PrefixOf<#partial_type> {}
+            }
+        });
         quote! {
+            #( #prefixes )*
+            #( #partial_prefixes )*
+
             impl<#( #types: LexOrdSer ),*> LexOrdSer for ( #( #types, )* ) {
-                fn to_write(&self, writer: &mut impl Write) -> Result {
+                const MAX_ENCODED_LEN: Option<usize> = {
+                    let len = Some(0);
+                    #( let len = len::sum(len, #types::MAX_ENCODED_LEN); )*
+                    len
+                };
+
+                fn to_write(&self, writer: &mut impl Sink) -> Result {
                     #( #types::to_write(&self.#index, writer)?; )*
                     Ok(())
                 }
-                fn to_write_seq(&self, writer: &mut impl Write) -> Result {
+                fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
                     #first_type::to_write_seq(&self.0, writer)?;
                     #( #types_no_first::to_write(&self.#index_no_first, writer)?; )*
                     Ok(())
                 }
+                fn encoded_len(&self) -> usize {
+                    0 #( + #types::encoded_len(&self.#index) )*
+                }
+                fn encoded_len_seq(&self) -> usize {
+                    #first_type::encoded_len_seq(&self.0)
+                        #( + #types_no_first::encoded_len(&self.#index_no_first) )*
+                }
             }
 
             impl<#( #types: LexOrd ),*> LexOrd for ( #( #types, )* ) {
-                fn from_read(reader: &mut impl Read) -> Result<Self> {
-                    Ok(( #( #types::from_read(reader)?, )* ))
-                }
-                fn from_read_seq(first: u8, reader: &mut impl Read) -> Result<Self> {
-                    Ok((
-                        #first_type::from_read_seq(first, reader)?,
-                        #( #types_no_first::from_read(reader)?, )*
-                    ))
+                fn from_read(reader: &mut impl Source) -> Result<Self> {
+                    nested(reader, |reader| Ok(( #(
+                        #types::from_read(reader).map_err(|error| error.with_field(#names))?,
+                    )* )))
+                }
+                fn from_read_seq(first: u8, reader: &mut impl Source) -> Result<Self> {
+                    nested(reader, |reader| Ok((
+                        #first_type::from_read_seq(first, reader)
+                            .map_err(|error| error.with_field(#first_name))?,
+                        #(
+                            #types_no_first::from_read(reader)
+                                .map_err(|error| error.with_field(#names_no_first))?,
+                        )*
+                    )))
+                }
+            }
+
+            impl<'de, #( #types: LexOrdRef<'de> ),*> LexOrdRef<'de> for ( #( #types, )* ) {
+                fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+                    nested(slice, |slice| Ok(( #(
+                        #types::from_slice(slice).map_err(|error| error.with_field(#names))?,
+                    )* )))
+                }
+                fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+                    nested(slice, |slice| Ok((
+                        #first_type::from_slice_seq(slice)
+                            .map_err(|error| error.with_field(#first_name))?,
+                        #(
+                            #types_no_first::from_slice(slice)
+                                .map_err(|error| error.with_field(#names_no_first))?,
+                        )*
+                    )))
                 }
             }
         }
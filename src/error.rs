@@ -0,0 +1,217 @@
+use alloc::vec::Vec;
+use core::{convert::Infallible, fmt::Display};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    UnexpectedEof,
+    BadVarIntPrefix(u8),
+    BadTag(u8),
+    UnknownVariant(usize),
+    InvalidUtf8(core::str::Utf8Error),
+    Overflow(core::num::TryFromIntError),
+    TrailingBytes(usize),
+    NonCanonical,
+    TooLong(usize),
+    TooManyElements(usize),
+    TooDeep(usize),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            ErrorKind::Io(error) => write!(f, "IO error: {error}"),
+            ErrorKind::UnexpectedEof => write!(f, "Unexpected end of input"),
+            ErrorKind::BadVarIntPrefix(prefix) => {
+                write!(f, "Unsupported VarInt prefix: {prefix:#04X}")
+            }
+            ErrorKind::BadTag(tag) => write!(f, "Unexpected tag: {tag:#04X}"),
+            ErrorKind::UnknownVariant(variant) => write!(f, "Unexpected enum variant: {variant}"),
+            ErrorKind::InvalidUtf8(error) => write!(f, "Invalid UTF-8: {error}"),
+            ErrorKind::Overflow(error) => write!(f, "Integer overflow: {error}"),
+            ErrorKind::TrailingBytes(len) => write!(f, "Trailing bytes: {len}"),
+            ErrorKind::NonCanonical => write!(f, "Non-canonical encoding"),
+            ErrorKind::TooLong(max_len) => write!(f, "Input longer than {max_len} bytes"),
+            ErrorKind::TooManyElements(max_elements) => {
+                write!(f, "Sequence longer than {max_elements} elements")
+            }
+            ErrorKind::TooDeep(max_depth) => write!(f, "Nesting deeper than {max_depth} levels"),
+        }
+    }
+}
+
+impl core::error::Error for ErrorKind {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            ErrorKind::Io(error) => Some(error),
+            ErrorKind::InvalidUtf8(error) => Some(error),
+            ErrorKind::Overflow(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<core::str::Utf8Error> for ErrorKind {
+    fn from(error: core::str::Utf8Error) -> Self {
+        ErrorKind::InvalidUtf8(error)
+    }
+}
+
+impl From<core::num::TryFromIntError> for ErrorKind {
+    fn from(error: core::num::TryFromIntError) -> Self {
+        ErrorKind::Overflow(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Field(&'static str),
+    Index(usize),
+}
+
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    position: Option<usize>,
+    type_name: Option<&'static str>,
+    path: Vec<PathSegment>,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Self {
+        Error {
+            kind,
+            position: None,
+            type_name: None,
+            path: Vec::new(),
+        }
+    }
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+    pub fn type_name(&self) -> Option<&'static str> {
+        self.type_name
+    }
+    pub fn path(&self) -> impl Iterator<Item = &PathSegment> {
+        self.path.iter().rev()
+    }
+    pub fn with_field(mut self, field: &'static str) -> Self {
+        self.path.push(PathSegment::Field(field));
+        self.type_name = None;
+        self
+    }
+    pub fn with_index(mut self, index: usize) -> Self {
+        self.path.push(PathSegment::Index(index));
+        self.type_name = None;
+        self
+    }
+    pub fn with_type(mut self, type_name: &'static str) -> Self {
+        self.type_name = Some(type_name);
+        self
+    }
+    pub(crate) fn with_position(mut self, position: usize) -> Self {
+        self.position.get_or_insert(position);
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(position) = self.position {
+            write!(f, " after {position} bytes")?;
+        }
+        if self.type_name.is_none() && self.path.is_empty() {
+            return Ok(());
+        }
+        write!(f, " in {}", self.type_name.unwrap_or_default())?;
+        for (index, segment) in self.path().enumerate() {
+            match segment {
+                PathSegment::Field(field) if index == 0 && self.type_name.is_none() => {
+                    write!(f, "{field}")?
+                }
+                PathSegment::Field(field) => write!(f, ".{field}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        core::error::Error::source(&self.kind)
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error::new(kind)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::UnexpectedEof => ErrorKind::UnexpectedEof.into(),
+            _ => ErrorKind::Io(error).into(),
+        }
+    }
+}
+
+impl From<core::str::Utf8Error> for Error {
+    fn from(error: core::str::Utf8Error) -> Self {
+        ErrorKind::from(error).into()
+    }
+}
+
+impl From<alloc::string::FromUtf8Error> for Error {
+    fn from(error: alloc::string::FromUtf8Error) -> Self {
+        ErrorKind::from(error.utf8_error()).into()
+    }
+}
+
+impl From<core::num::TryFromIntError> for Error {
+    fn from(error: core::num::TryFromIntError) -> Self {
+        ErrorKind::from(error).into()
+    }
+}
+
+impl From<Infallible> for Error {
+    fn from(_: Infallible) -> Self {
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::{Error, ErrorKind};
+
+    #[test]
+    fn test_error_display() {
+        let error = Error::new(ErrorKind::BadVarIntPrefix(0x02));
+        assert_snapshot!(error, @"Unsupported VarInt prefix: 0x02");
+        let error = error
+            .with_field("sku")
+            .with_type("Item")
+            .with_index(3)
+            .with_field("items")
+            .with_type("Order")
+            .with_position(7);
+        assert_snapshot!(error, @"Unsupported VarInt prefix: 0x02 after 7 bytes in Order.items[3].sku");
+        let error = Error::new(ErrorKind::UnexpectedEof)
+            .with_index(2)
+            .with_field("1");
+        assert_snapshot!(error, @"Unexpected end of input in 1[2]");
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::{DecodeContext, DecodeOptions, ErrorKind, Result};
+
+pub trait Sink {
+    fn write_all(&mut self, buf: &[u8]) -> Result;
+}
+
+pub trait Source {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result;
+    // The next byte without consuming it, or `None` at the end of the input.
+    fn peek(&mut self) -> Result<Option<u8>>;
+    // Readers that wrap another source forward its context.
+    fn context(&mut self) -> Option<&mut DecodeContext> {
+        None
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> Sink for W {
+    fn write_all(&mut self, buf: &[u8]) -> Result {
+        Ok(std::io::Write::write_all(self, buf)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead + ?Sized> Source for R {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result {
+        Ok(std::io::Read::read_exact(self, buf)?)
+    }
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(std::io::BufRead::fill_buf(self)?.first().copied())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Sink for Vec<u8> {
+    fn write_all(&mut self, buf: &[u8]) -> Result {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<S: Sink + ?Sized> Sink for &mut S {
+    fn write_all(&mut self, buf: &[u8]) -> Result {
+        S::write_all(self, buf)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Source for &[u8] {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result {
+        read_slice(self, buf)
+    }
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.first().copied())
+    }
+}
+
+fn read_slice(slice: &mut &[u8], buf: &mut [u8]) -> Result {
+    if buf.len() > slice.len() {
+        *slice = &slice[slice.len()..];
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    let (bytes, rest) = slice.split_at(buf.len());
+    buf.copy_from_slice(bytes);
+    *slice = rest;
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+impl<S: Source + ?Sized> Source for &mut S {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result {
+        S::read_exact(self, buf)
+    }
+    fn peek(&mut self) -> Result<Option<u8>> {
+        S::peek(self)
+    }
+    fn context(&mut self) -> Option<&mut DecodeContext> {
+        S::context(self)
+    }
+}
+
+// The input of `LexOrdRef::from_slice`, which borrows from the bytes it has not read yet.
+pub struct SliceReader<'de> {
+    bytes: &'de [u8],
+    context: DecodeContext,
+}
+
+impl<'de> SliceReader<'de> {
+    pub fn new(bytes: &'de [u8]) -> Self {
+        Self::with_options(bytes, DecodeOptions::default())
+    }
+    pub(crate) fn with_options(bytes: &'de [u8], options: DecodeOptions) -> Self {
+        SliceReader {
+            bytes,
+            context: DecodeContext::new(options),
+        }
+    }
+    pub fn as_bytes(&self) -> &'de [u8] {
+        self.bytes
+    }
+    pub fn advance(&mut self, len: usize) {
+        self.bytes = &self.bytes[len..];
+    }
+}
+
+impl Source for SliceReader<'_> {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result {
+        read_slice(&mut self.bytes, buf)
+    }
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.bytes.first().copied())
+    }
+    fn context(&mut self) -> Option<&mut DecodeContext> {
+        Some(&mut self.context)
+    }
+}
+
+pub(crate) struct Counter(pub(crate) usize);
+
+impl Sink for Counter {
+    fn write_all(&mut self, buf: &[u8]) -> Result {
+        self.0 += buf.len();
+        Ok(())
+    }
+}
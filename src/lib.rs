@@ -1,43 +1,37 @@
-mod stdlib;
-pub mod util;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-pub use lexord_derive::LexOrd;
+extern crate alloc;
 
-use std::{
-    convert::Infallible,
-    io::{Read, Write},
-};
+mod error;
+pub mod io;
+mod nulls_last;
+mod options;
+mod partial;
+pub mod range;
+mod reverse;
+mod stdlib;
+pub mod util;
 
-use thiserror::Error;
+pub use error::{Error, ErrorKind, PathSegment};
+pub use lexord_derive::{LexOrd, LexOrdSer};
+pub use nulls_last::NullsLast;
+pub use options::{DecodeContext, DecodeOptions};
+pub use partial::{BytesPrefix, StrPrefix};
+pub use reverse::Reverse;
 
-#[derive(Error, Debug)]
-pub enum Error {
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
-    #[error("UTF-8 error: {0}")]
-    Utf8(#[from] std::string::FromUtf8Error),
-    #[error("Int parse error: {0}")]
-    FromInt(#[from] std::num::TryFromIntError),
-    #[error("Parse error: {0}")]
-    Parse(String),
-    #[error("Internal error: {0}")]
-    Internal(String),
-}
+use alloc::vec::Vec;
 
-impl From<Infallible> for Error {
-    fn from(_: Infallible) -> Self {
-        unreachable!()
-    }
-}
+use io::{Counter, Sink, SliceReader, Source};
+use util::iterator::SliceIter;
 
-pub type Result<T = ()> = std::result::Result<T, Error>;
+pub type Result<T = ()> = core::result::Result<T, Error>;
 
-struct SeqWriter<'a, W: Write> {
+struct SeqWriter<'a, W: Sink> {
     writer: &'a mut W,
     start: bool,
 }
 
-impl<'a, W: Write> SeqWriter<'a, W> {
+impl<'a, W: Sink> SeqWriter<'a, W> {
     fn new(writer: &'a mut W) -> Self {
         SeqWriter {
             writer,
@@ -46,23 +40,20 @@ impl<'a, W: Write> SeqWriter<'a, W> {
     }
 }
 
-impl<'a, W: Write> Write for SeqWriter<'a, W> {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+impl<'a, W: Sink> Sink for SeqWriter<'a, W> {
+    fn write_all(&mut self, buf: &[u8]) -> Result {
         if buf.is_empty() {
-            return Ok(0);
+            return Ok(());
         }
         if self.start && buf[0] <= 0x01 {
             self.writer.write_all(&[0x01])?;
         }
         self.start = false;
-        self.writer.write(buf)
-    }
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.writer.flush()
+        self.writer.write_all(buf)
     }
 }
 
-impl<'a, W: Write> Drop for SeqWriter<'a, W> {
+impl<'a, W: Sink> Drop for SeqWriter<'a, W> {
     fn drop(&mut self) {
         if self.start {
             self.writer.write_all(&[0x01]).unwrap();
@@ -70,29 +61,217 @@ impl<'a, W: Write> Drop for SeqWriter<'a, W> {
     }
 }
 
+// The sequence encoding of a value whose encoding is `bytes`, for types that know their
+// bytes up front and need no `SeqWriter` to find the first one.
+pub(crate) fn write_seq_bytes(writer: &mut impl Sink, bytes: &[u8]) -> Result {
+    if bytes.first().is_none_or(|&first| first <= 0x01) {
+        writer.write_all(&[0x01])?;
+    }
+    writer.write_all(bytes)
+}
+
 pub trait LexOrdSer: PartialOrd {
-    fn to_write(&self, writer: &mut impl Write) -> Result;
-    fn to_write_seq(&self, writer: &mut impl Write) -> Result {
+    const MAX_ENCODED_LEN: Option<usize> = None;
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result;
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
         self.to_write(&mut SeqWriter::new(writer))
     }
+    fn to_write_items(items: &[Self], writer: &mut impl Sink) -> Result
+    where
+        Self: Sized,
+    {
+        for item in items {
+            item.to_write_seq(writer)?;
+        }
+        Ok(())
+    }
+    fn encoded_len(&self) -> usize {
+        let mut counter = Counter(0);
+        self.to_write(&mut counter)
+            .expect("value cannot be encoded");
+        counter.0
+    }
+    fn encoded_len_seq(&self) -> usize {
+        let mut counter = Counter(0);
+        self.to_write_seq(&mut counter)
+            .expect("value cannot be encoded");
+        counter.0
+    }
 }
 
-impl<T: LexOrdSer> LexOrdSer for &T {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
+impl<T: LexOrdSer + ?Sized> LexOrdSer for &T {
+    const MAX_ENCODED_LEN: Option<usize> = T::MAX_ENCODED_LEN;
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
         T::to_write(self, writer)
     }
-    fn to_write_seq(&self, writer: &mut impl Write) -> Result {
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
         T::to_write_seq(self, writer)
     }
+    fn encoded_len(&self) -> usize {
+        T::encoded_len(self)
+    }
+    fn encoded_len_seq(&self) -> usize {
+        T::encoded_len_seq(self)
+    }
+}
+
+pub trait PrefixOf<T: ?Sized>: LexOrdSer {}
+
+// Declared with `#[lexord(encodes_as = "T")]`, for encode-only types such as borrowed keys
+// that write the same bytes as `T`, as `util::test::check_encodes_as` tests.
+pub trait EncodesAs<T: ?Sized>: LexOrdSer {}
+
+pub trait LexOrdWith<T> {
+    const MAX_ENCODED_LEN: Option<usize> = None;
+
+    fn to_write(value: &T, writer: &mut impl Sink) -> Result;
+    fn encoded_len(value: &T) -> usize {
+        let mut counter = Counter(0);
+        Self::to_write(value, &mut counter).expect("value cannot be encoded");
+        counter.0
+    }
+    fn from_read(reader: &mut impl Source) -> Result<T>;
+    // Byte strings are totally ordered, so a codec that agrees with its encoding is as well.
+    fn cmp(a: &T, b: &T) -> core::cmp::Ordering;
 }
 
 pub trait LexOrd: Sized + LexOrdSer {
-    fn from_read(reader: &mut impl Read) -> Result<Self>;
-    fn from_read_seq(first: u8, reader: &mut impl Read) -> Result<Self> {
+    fn from_read(reader: &mut impl Source) -> Result<Self>;
+    // `first` is the next byte of `reader`, which the caller has peeked but not consumed.
+    fn from_read_seq(first: u8, reader: &mut impl Source) -> Result<Self> {
+        let strict = options::strict(reader);
         if first == 0x01 {
-            Self::from_read(reader)
-        } else {
-            Self::from_read(&mut [first].chain(reader))
+            reader.read_exact(&mut [0])?;
+            let next = if strict { reader.peek()? } else { None };
+            let value = Self::from_read(reader)?;
+            // The escape is only written before an empty encoding or one starting with 0x00
+            // or 0x01.
+            if next.is_some_and(|next| next > 0x01) && value.encoded_len() > 0 {
+                return Err(ErrorKind::NonCanonical.into());
+            }
+            return Ok(value);
+        }
+        let value = Self::from_read(reader)?;
+        // An empty value leaves `first` unread, and is escaped when encoded.
+        if Self::MAX_ENCODED_LEN == Some(0) || (strict && value.encoded_len() == 0) {
+            if strict {
+                return Err(ErrorKind::NonCanonical.into());
+            }
+            reader.read_exact(&mut [0])?;
         }
+        Ok(value)
     }
 }
+
+pub trait LexOrdRef<'de>: Sized + LexOrdSer {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self>;
+    fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+        match slice.as_bytes() {
+            [0x01, rest @ ..] => {
+                slice.advance(1);
+                let value = Self::from_slice(slice)?;
+                if options::strict(slice) && slice.as_bytes().len() < rest.len() && rest[0] > 0x01 {
+                    return Err(ErrorKind::NonCanonical.into());
+                }
+                Ok(value)
+            }
+            [_, rest @ ..] => {
+                let value = Self::from_slice(slice)?;
+                if slice.as_bytes().len() > rest.len() {
+                    if options::strict(slice) {
+                        return Err(ErrorKind::NonCanonical.into());
+                    }
+                    slice.advance(1);
+                }
+                Ok(value)
+            }
+            [] => Self::from_slice(slice),
+        }
+    }
+    fn from_slice_items(slice: &mut SliceReader<'de>) -> Result<Vec<Self>> {
+        SliceIter::new(slice).collect()
+    }
+}
+
+pub fn to_vec<T: LexOrdSer + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    value.to_write(&mut bytes)?;
+    Ok(bytes)
+}
+
+pub fn to_vec_prefix<T: ?Sized, P: PrefixOf<T>>(prefix: &P) -> Result<Vec<u8>> {
+    to_vec(prefix)
+}
+
+pub fn to_vec_as<T: ?Sized, E: EncodesAs<T>>(value: &E) -> Result<Vec<u8>> {
+    to_vec(value)
+}
+
+pub fn from_slice<'de, T: LexOrdRef<'de>>(bytes: &'de [u8]) -> Result<T> {
+    from_slice_with(bytes, DecodeOptions::default())
+}
+
+pub fn from_slice_with<'de, T: LexOrdRef<'de>>(
+    bytes: &'de [u8],
+    options: DecodeOptions,
+) -> Result<T> {
+    if let Some(max_len) = options.max_len.filter(|&max_len| bytes.len() > max_len) {
+        return Err(Error::new(ErrorKind::TooLong(max_len)).with_position(max_len));
+    }
+    let mut slice = SliceReader::with_options(bytes, options);
+    let value = T::from_slice(&mut slice)
+        .map_err(|error| error.with_position(bytes.len() - slice.as_bytes().len()))?;
+    let rest = slice.as_bytes();
+    if !rest.is_empty() {
+        return Err(Error::new(ErrorKind::TrailingBytes(rest.len()))
+            .with_position(bytes.len() - rest.len()));
+    }
+    Ok(value)
+}
+
+pub fn is_canonical<'de, T: LexOrdRef<'de>>(bytes: &'de [u8]) -> bool {
+    from_slice_with::<T>(bytes, DecodeOptions::strict()).is_ok()
+}
+
+struct CountingReader<R: Source> {
+    reader: R,
+    position: usize,
+    context: DecodeContext,
+}
+
+impl<R: Source> Source for CountingReader<R> {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result {
+        if let Some(max_len) = self.context.options().max_len {
+            let remaining = max_len - self.position;
+            if buf.len() > remaining {
+                self.reader.read_exact(&mut buf[..remaining + 1])?;
+                self.position = max_len;
+                return Err(ErrorKind::TooLong(max_len).into());
+            }
+        }
+        self.reader.read_exact(buf)?;
+        self.position += buf.len();
+        Ok(())
+    }
+    fn peek(&mut self) -> Result<Option<u8>> {
+        self.reader.peek()
+    }
+    fn context(&mut self) -> Option<&mut DecodeContext> {
+        Some(&mut self.context)
+    }
+}
+
+pub fn from_reader<T: LexOrd>(reader: impl Source) -> Result<T> {
+    from_reader_with(reader, DecodeOptions::default())
+}
+
+pub fn from_reader_with<T: LexOrd>(reader: impl Source, options: DecodeOptions) -> Result<T> {
+    let mut reader = CountingReader {
+        reader,
+        position: 0,
+        context: DecodeContext::new(options),
+    };
+    T::from_read(&mut reader).map_err(|error| error.with_position(reader.position))
+}
@@ -0,0 +1,92 @@
+use core::cmp::Ordering;
+
+use crate::{
+    io::{Sink, SliceReader, Source},
+    stdlib::option::{read_option, slice_option, write_option},
+    LexOrd, LexOrdRef, LexOrdSer, Result,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NullsLast<T>(pub T);
+
+impl<T: PartialOrd> PartialOrd for NullsLast<Option<T>> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (&self.0, &other.0) {
+            (None, None) => Some(Ordering::Equal),
+            (None, Some(_)) => Some(Ordering::Greater),
+            (Some(_), None) => Some(Ordering::Less),
+            (Some(a), Some(b)) => a.partial_cmp(b),
+        }
+    }
+}
+
+impl<T: Ord> Ord for NullsLast<Option<T>> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.0, &other.0) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => a.cmp(b),
+        }
+    }
+}
+
+const TAGS: [u8; 2] = [0x81, 0x80];
+
+impl<T: LexOrdSer> LexOrdSer for NullsLast<Option<T>> {
+    const MAX_ENCODED_LEN: Option<usize> = Option::<T>::MAX_ENCODED_LEN;
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        write_option(self.0.as_ref(), TAGS, writer)
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        self.to_write(writer)
+    }
+    fn encoded_len(&self) -> usize {
+        self.0.encoded_len()
+    }
+    fn encoded_len_seq(&self) -> usize {
+        self.0.encoded_len_seq()
+    }
+}
+
+impl<T: LexOrd> LexOrd for NullsLast<Option<T>> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
+        Ok(NullsLast(read_option(TAGS, reader)?))
+    }
+    fn from_read_seq(_first: u8, reader: &mut impl Source) -> Result<Self> {
+        Self::from_read(reader)
+    }
+}
+
+impl<'de, T: LexOrdRef<'de>> LexOrdRef<'de> for NullsLast<Option<T>> {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Ok(NullsLast(slice_option(TAGS, slice)?))
+    }
+    fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_slice(slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use crate::util::test::encode;
+
+    use super::NullsLast;
+
+    #[test]
+    fn test_nulls_last_format() {
+        assert_snapshot!(encode(NullsLast(None::<u8>)), @"81");
+        assert_snapshot!(encode(NullsLast(Some(0u8))), @"80 00");
+        assert_snapshot!(encode(vec![NullsLast(Some(1u8)), NullsLast(None)]), @"80 01 81 00");
+    }
+
+    #[test]
+    fn test_nulls_last_order() {
+        assert!(NullsLast(Some(u8::MAX)) < NullsLast(None));
+        assert!(NullsLast(Some(1u8)) < NullsLast(Some(2u8)));
+        assert_snapshot!(encode(NullsLast(Some(u8::MAX))), @"80 FF");
+    }
+}
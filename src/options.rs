@@ -0,0 +1,209 @@
+use crate::{io::Source, ErrorKind, Result};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOptions {
+    pub strict: bool,
+    pub max_len: Option<usize>,
+    pub max_elements: Option<usize>,
+    pub max_depth: Option<usize>,
+}
+
+impl DecodeOptions {
+    pub fn strict() -> Self {
+        DecodeOptions {
+            strict: true,
+            ..Default::default()
+        }
+    }
+}
+
+// The state of a single decode, carried by the reader or slice it decodes from so that
+// concurrent decodes never see each other's options or nesting.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DecodeContext {
+    options: DecodeOptions,
+    depth: usize,
+}
+
+impl DecodeContext {
+    pub(crate) fn new(options: DecodeOptions) -> Self {
+        DecodeContext { options, depth: 0 }
+    }
+    pub(crate) fn options(&self) -> DecodeOptions {
+        self.options
+    }
+}
+
+// Sources without a context decode with the default options.
+pub(crate) fn strict(reader: &mut impl Source) -> bool {
+    reader
+        .context()
+        .is_some_and(|context| context.options.strict)
+}
+
+pub(crate) fn check_elements(reader: &mut impl Source, count: usize) -> Result {
+    match reader
+        .context()
+        .and_then(|context| context.options.max_elements)
+    {
+        Some(max_elements) if count > max_elements => {
+            Err(ErrorKind::TooManyElements(max_elements).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+// Fails if decoding one level deeper than the reader is now would exceed the limit.
+pub(crate) fn check_depth(reader: &mut impl Source) -> Result {
+    let Some(context) = reader.context() else {
+        return Ok(());
+    };
+    match context.options.max_depth {
+        Some(max_depth) if context.depth >= max_depth => Err(ErrorKind::TooDeep(max_depth).into()),
+        _ => Ok(()),
+    }
+}
+
+pub fn nested<R: Source, T>(reader: &mut R, decode: impl FnOnce(&mut R) -> Result<T>) -> Result<T> {
+    check_depth(reader)?;
+    if let Some(context) = reader.context() {
+        context.depth += 1;
+    }
+    // The depth is restored on errors as well, since a caller may recover from them.
+    let result = decode(reader);
+    if let Some(context) = reader.context() {
+        context.depth -= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use crate::{
+        from_reader_with, from_slice, from_slice_with, is_canonical, to_vec, DecodeOptions, LexOrd,
+        LexOrdRef,
+    };
+
+    fn check_non_canonical<T: LexOrd + for<'de> LexOrdRef<'de>>(bytes: &[u8]) {
+        assert!(from_slice::<T>(bytes).is_ok(), "{bytes:x?}");
+        assert!(!is_canonical::<T>(bytes), "{bytes:x?}");
+        assert!(from_reader_with::<T>(bytes, DecodeOptions::strict()).is_err());
+    }
+
+    #[test]
+    fn test_strict() {
+        check_non_canonical::<bool>(b"\x82");
+        check_non_canonical::<u16>(b"\xC0\x05");
+        check_non_canonical::<u64>(b"\xF0\x00\x00\x00\x00\x00\x00\x05");
+        check_non_canonical::<i32>(b"\x3F\xFF");
+        check_non_canonical::<f32>(b"\x7F\xFF\xFF\xFF");
+        check_non_canonical::<f64>(b"\x7F\xFF\xFF\xFF\xFF\xFF\xFF\xFF");
+        check_non_canonical::<Vec<u8>>(b"\x01\x05\x00");
+        check_non_canonical::<String>(b"a\x01b\x00");
+        check_non_canonical::<Vec<()>>(b"\x05\x00");
+        check_non_canonical::<Vec<[u8; 0]>>(b"\x05\x00");
+        check_non_canonical::<Vec<(u8, u16)>>(b"\x01\x02\x80\x00");
+        for value in [f32::NAN, -f32::NAN, f32::from_bits(0x7FC0_0001)] {
+            assert!(is_canonical::<f32>(&to_vec(&value).unwrap()), "{value}");
+        }
+        for value in [f64::NAN, -f64::NAN, f64::from_bits(0xFFF0_0000_0000_0001)] {
+            assert!(is_canonical::<f64>(&to_vec(&value).unwrap()), "{value}");
+        }
+        assert!(is_canonical::<Vec<u8>>(b"\x01\x01\x05\x00"));
+        assert!(is_canonical::<Vec<((), u16)>>(b"\x01\x85\x00"));
+        assert!(from_slice::<Vec<Vec<u8>>>(b"\x05\x00").is_err());
+    }
+
+    fn decode<T: LexOrd + for<'de> LexOrdRef<'de>>(bytes: &[u8], options: DecodeOptions) -> String {
+        let from_reader = from_reader_with::<T>(bytes, options);
+        match from_slice_with::<T>(bytes, options) {
+            Ok(_) => {
+                assert!(from_reader.is_ok());
+                "ok".to_string()
+            }
+            Err(error) => {
+                assert_eq!(
+                    error.kind().to_string(),
+                    from_reader.err().unwrap().kind().to_string()
+                );
+                error.to_string()
+            }
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let max_len = DecodeOptions {
+            max_len: Some(3),
+            ..Default::default()
+        };
+        assert_snapshot!(decode::<String>(b"ab\x00", max_len), @"ok");
+        assert_snapshot!(decode::<String>(b"abc\x00", max_len), @"Input longer than 3 bytes after 3 bytes");
+        let max_elements = DecodeOptions {
+            max_elements: Some(2),
+            ..Default::default()
+        };
+        assert_snapshot!(decode::<Vec<u16>>(b"\x81\x82\x00", max_elements), @"ok");
+        assert_snapshot!(decode::<Vec<u16>>(b"\x81\x82\x83\x00", max_elements), @"Sequence longer than 2 elements after 2 bytes in [2]");
+        assert_snapshot!(decode::<String>(b"a\x01\x00\x00", max_elements), @"ok");
+        assert_snapshot!(decode::<String>(b"abc\x00", max_elements), @"Sequence longer than 2 elements after 2 bytes in [2]");
+        assert_snapshot!(decode::<(u8, Vec<()>)>(b"\x05\x01\x01\x01\x00", max_elements), @"Sequence longer than 2 elements after 3 bytes in 1[2]");
+        let max_depth = DecodeOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_snapshot!(decode::<Vec<Vec<u8>>>(b"\x01\x05\x00\x00", max_depth), @"ok");
+        assert_snapshot!(decode::<Vec<Vec<Vec<u8>>>>(b"\x01\x01\x05\x00\x00\x00", max_depth), @"Nesting deeper than 2 levels after 2 bytes in [0][0][0]");
+        assert_snapshot!(decode::<Vec<(u8, (u8,))>>(b"\x05\x06\x00", max_depth), @"Nesting deeper than 2 levels after 1 bytes in [0].1");
+        assert_snapshot!(decode::<Vec<Vec<Vec<u8>>>>(b"\x00", max_depth), @"ok");
+    }
+
+    fn decode_both<T: LexOrd + for<'de> LexOrdRef<'de>>(
+        bytes: &[u8],
+        options: DecodeOptions,
+    ) -> String {
+        let from_slice = from_slice_with::<T>(bytes, options).err().unwrap();
+        let from_reader = from_reader_with::<T>(bytes, options).err().unwrap();
+        assert_eq!(from_slice.to_string(), from_reader.to_string());
+        assert_eq!(from_slice.position(), from_reader.position());
+        from_slice.to_string()
+    }
+
+    #[test]
+    fn test_bytes_fast_path_errors() {
+        let max_elements = DecodeOptions {
+            max_elements: Some(10),
+            ..Default::default()
+        };
+        let bytes = [0x05; 12];
+        assert_snapshot!(decode_both::<Vec<u8>>(&bytes, max_elements), @"Sequence longer than 10 elements after 10 bytes in [10]");
+        assert_snapshot!(decode_both::<String>(b"ab\x01\x00cdefghijk\x00", max_elements), @"Sequence longer than 10 elements after 11 bytes in [10]");
+        assert_snapshot!(decode_both::<Vec<u8>>(b"a\x01\x05\x00", DecodeOptions::strict()), @"Non-canonical encoding after 3 bytes in [1]");
+        assert_snapshot!(decode_both::<Vec<u8>>(b"ab", DecodeOptions::default()), @"Unexpected end of input after 2 bytes");
+        let max_depth = DecodeOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        assert_snapshot!(decode_both::<Vec<Vec<u8>>>(b"\x01\x05\x00\x00", max_depth), @"Nesting deeper than 1 levels after 1 bytes in [0][0]");
+    }
+
+    #[test]
+    fn test_concurrent_decodes() {
+        std::thread::scope(|scope| {
+            for strict in [false, true] {
+                scope.spawn(move || {
+                    let options = DecodeOptions {
+                        strict,
+                        ..Default::default()
+                    };
+                    for _ in 0..1000 {
+                        let decoded = from_slice_with::<u16>(b"\xC0\x05", options);
+                        assert_eq!(decoded.is_ok(), !strict);
+                    }
+                });
+            }
+        });
+    }
+}
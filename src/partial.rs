@@ -0,0 +1,108 @@
+use alloc::{borrow::Cow, boxed::Box, rc::Rc, string::String, sync::Arc, vec::Vec};
+
+use crate::{io::Sink, LexOrdSer, PrefixOf, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StrPrefix<'a>(pub &'a str);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BytesPrefix<'a>(pub &'a [u8]);
+
+impl LexOrdSer for StrPrefix<'_> {
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        u8::to_write_items(self.0.as_bytes(), writer)
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        // An empty prefix matches every element, escaped or not.
+        if self.0.as_bytes().first().is_some_and(|&byte| byte <= 0x01) {
+            writer.write_all(&[0x01])?;
+        }
+        self.to_write(writer)
+    }
+    fn encoded_len(&self) -> usize {
+        self.0.as_bytes().encoded_len() - 1
+    }
+    fn encoded_len_seq(&self) -> usize {
+        let escape = self.0.as_bytes().first().is_some_and(|&byte| byte <= 0x01);
+        self.encoded_len() + escape as usize
+    }
+}
+
+impl LexOrdSer for BytesPrefix<'_> {
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        u8::to_write_items(self.0, writer)
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        writer.write_all(&[0x01])?;
+        self.to_write(writer)
+    }
+    fn encoded_len(&self) -> usize {
+        self.0.encoded_len() - 1
+    }
+    fn encoded_len_seq(&self) -> usize {
+        self.encoded_len() + 1
+    }
+}
+
+impl PrefixOf<str> for StrPrefix<'_> {}
+impl PrefixOf<String> for StrPrefix<'_> {}
+impl PrefixOf<Cow<'_, str>> for StrPrefix<'_> {}
+impl PrefixOf<Box<str>> for StrPrefix<'_> {}
+impl PrefixOf<Rc<str>> for StrPrefix<'_> {}
+impl PrefixOf<Arc<str>> for StrPrefix<'_> {}
+
+impl PrefixOf<[u8]> for BytesPrefix<'_> {}
+impl PrefixOf<Vec<u8>> for BytesPrefix<'_> {}
+impl PrefixOf<Cow<'_, [u8]>> for BytesPrefix<'_> {}
+impl PrefixOf<Box<[u8]>> for BytesPrefix<'_> {}
+impl PrefixOf<Rc<[u8]>> for BytesPrefix<'_> {}
+impl PrefixOf<Arc<[u8]>> for BytesPrefix<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use insta::assert_snapshot;
+
+    use crate::{to_vec, to_vec_prefix, LexOrdSer};
+
+    use super::{BytesPrefix, StrPrefix};
+
+    fn hex(bytes: Vec<u8>) -> String {
+        bytes
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_partial_prefix() {
+        assert_snapshot!(hex(to_vec_prefix::<String, _>(&StrPrefix("ab")).unwrap()), @"61 62");
+        assert_snapshot!(hex(to_vec_prefix::<String, _>(&StrPrefix("a\0")).unwrap()), @"61 01 00");
+        assert_snapshot!(hex(to_vec_prefix::<Vec<u8>, _>(&BytesPrefix(b"\x01\xFF")).unwrap()), @"01 01 FF");
+        for (value, prefix) in [("abc", "ab"), ("a\0\x01", "a\0"), ("\0", "\0"), ("", "")] {
+            let full = to_vec(value).unwrap();
+            let partial = to_vec(&StrPrefix(prefix)).unwrap();
+            assert!(full.starts_with(&partial), "{full:x?} {partial:x?}");
+            assert_eq!(StrPrefix(prefix).encoded_len(), partial.len());
+            let mut full_seq = vec![];
+            value.to_write_seq(&mut full_seq).unwrap();
+            let mut partial_seq = vec![];
+            StrPrefix(prefix).to_write_seq(&mut partial_seq).unwrap();
+            assert!(
+                full_seq.starts_with(&partial_seq),
+                "{full_seq:x?} {partial_seq:x?}"
+            );
+            assert_eq!(StrPrefix(prefix).encoded_len_seq(), partial_seq.len());
+        }
+        let full = to_vec(&Cow::Borrowed(b"\0\x02".as_slice())).unwrap();
+        let mut full_seq = vec![];
+        b"\0\x02".as_slice().to_write_seq(&mut full_seq).unwrap();
+        let mut partial_seq = vec![];
+        BytesPrefix(b"\0").to_write_seq(&mut partial_seq).unwrap();
+        assert!(full.starts_with(&to_vec(&BytesPrefix(b"\0")).unwrap()));
+        assert!(full_seq.starts_with(&partial_seq));
+        assert_eq!(BytesPrefix(b"\0").encoded_len_seq(), partial_seq.len());
+    }
+}
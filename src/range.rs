@@ -0,0 +1,219 @@
+use alloc::vec::Vec;
+use core::ops::{Bound, RangeBounds};
+
+use crate::{to_vec, LexOrdSer, PrefixOf, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ByteRange {
+    pub start: Vec<u8>,
+    pub end: Option<Vec<u8>>,
+}
+
+impl ByteRange {
+    fn new(start: Vec<u8>, end: Option<Vec<u8>>) -> Self {
+        // Keep empty ranges well-formed so they can be passed to `BTreeMap::range`.
+        let end = end.map(|end| if end < start { start.clone() } else { end });
+        ByteRange { start, end }
+    }
+}
+
+impl RangeBounds<[u8]> for ByteRange {
+    fn start_bound(&self) -> Bound<&[u8]> {
+        Bound::Included(&self.start)
+    }
+    fn end_bound(&self) -> Bound<&[u8]> {
+        match &self.end {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        }
+    }
+}
+
+impl RangeBounds<Vec<u8>> for ByteRange {
+    fn start_bound(&self) -> Bound<&Vec<u8>> {
+        Bound::Included(&self.start)
+    }
+    fn end_bound(&self) -> Bound<&Vec<u8>> {
+        match &self.end {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        }
+    }
+}
+
+pub fn successor(key: &[u8]) -> Vec<u8> {
+    let mut successor = Vec::with_capacity(key.len() + 1);
+    successor.extend_from_slice(key);
+    successor.push(0x00);
+    successor
+}
+
+pub fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let len = prefix.iter().rposition(|&byte| byte != 0xFF)? + 1;
+    let mut successor = prefix[..len].to_vec();
+    successor[len - 1] += 1;
+    Some(successor)
+}
+
+pub fn range<T: LexOrdSer>(bounds: impl RangeBounds<T>) -> Result<ByteRange> {
+    let start = match bounds.start_bound() {
+        Bound::Included(start) => to_vec(start)?,
+        Bound::Excluded(start) => successor(&to_vec(start)?),
+        Bound::Unbounded => Vec::new(),
+    };
+    let end = match bounds.end_bound() {
+        Bound::Included(end) => Some(successor(&to_vec(end)?)),
+        Bound::Excluded(end) => Some(to_vec(end)?),
+        Bound::Unbounded => None,
+    };
+    Ok(ByteRange::new(start, end))
+}
+
+pub fn prefix<T: ?Sized, P: PrefixOf<T>>(prefix: &P) -> Result<ByteRange> {
+    prefix_range::<T, P>((Bound::Included(prefix), Bound::Included(prefix)))
+}
+
+pub fn prefix_range<T: ?Sized, P: PrefixOf<T>>(bounds: impl RangeBounds<P>) -> Result<ByteRange> {
+    let start = match bounds.start_bound() {
+        Bound::Included(start) => to_vec(start)?,
+        Bound::Excluded(start) => match prefix_successor(&to_vec(start)?) {
+            Some(start) => start,
+            None => return Ok(ByteRange::new(Vec::new(), Some(Vec::new()))),
+        },
+        Bound::Unbounded => Vec::new(),
+    };
+    let end = match bounds.end_bound() {
+        Bound::Included(end) => prefix_successor(&to_vec(end)?),
+        Bound::Excluded(end) => Some(to_vec(end)?),
+        Bound::Unbounded => None,
+    };
+    Ok(ByteRange::new(start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, ops::Bound};
+
+    use insta::assert_debug_snapshot;
+
+    use crate::to_vec;
+
+    use super::{prefix, prefix_range, prefix_successor, range, ByteRange};
+
+    fn keys() -> BTreeMap<Vec<u8>, (u8, u16, String)> {
+        let mut keys = BTreeMap::new();
+        for a in [0u8, 1, 2, 0xFE, 0xFF] {
+            for b in [0u16, 10, 15, 20, 0x40, u16::MAX] {
+                for c in ["", "\0", "\x01", "a", "\u{FF}"] {
+                    let key = (a, b, c.to_string());
+                    keys.insert(to_vec(&key).unwrap(), key);
+                }
+            }
+        }
+        keys
+    }
+
+    fn select(range: &ByteRange) -> Vec<(u8, u16, String)> {
+        keys()
+            .range::<[u8], _>(range.clone())
+            .map(|(_, key)| key.clone())
+            .collect()
+    }
+
+    fn filter(f: impl Fn(&(u8, u16, String)) -> bool) -> Vec<(u8, u16, String)> {
+        keys().into_values().filter(f).collect()
+    }
+
+    #[test]
+    fn test_prefix_successor() {
+        assert_eq!(prefix_successor(b""), None);
+        assert_eq!(prefix_successor(b"\xFF\xFF"), None);
+        assert_eq!(prefix_successor(b"\x01\xFF"), Some(b"\x02".to_vec()));
+        assert_eq!(prefix_successor(b"\x01\x00"), Some(b"\x01\x01".to_vec()));
+    }
+
+    #[test]
+    fn test_range() {
+        type Key = (u8, u16, String);
+        let low: Key = (1, 10, "a".to_string());
+        let high: Key = (0xFF, 0x40, "\0".to_string());
+        let cases: [(Bound<&Key>, Bound<&Key>); 5] = [
+            (Bound::Included(&low), Bound::Included(&high)),
+            (Bound::Excluded(&low), Bound::Excluded(&high)),
+            (Bound::Unbounded, Bound::Included(&low)),
+            (Bound::Excluded(&high), Bound::Unbounded),
+            (Bound::Excluded(&high), Bound::Excluded(&low)),
+        ];
+        for bounds in cases {
+            let byte_range = range::<Key>(bounds).unwrap();
+            assert_eq!(
+                select(&byte_range),
+                filter(|key| std::ops::RangeBounds::contains(&bounds, key))
+            );
+        }
+        assert_debug_snapshot!(range(5u16..=6).unwrap(), @r#"
+ByteRange {
+    start: [
+        133,
+    ],
+    end: Some(
+        [
+            134,
+            0,
+        ],
+    ),
+}
+"#);
+    }
+
+    #[test]
+    fn test_prefix_range() {
+        type Key = (u8, u16, String);
+        assert_eq!(
+            select(&prefix::<Key, _>(&(1u8, 15u16)).unwrap()),
+            filter(|key| key.0 == 1 && key.1 == 15)
+        );
+        assert_eq!(
+            select(&prefix_range::<Key, _>((1u8, 10u16)..=(1u8, 20u16)).unwrap()),
+            filter(|key| key.0 == 1 && (10..=20).contains(&key.1))
+        );
+        assert_eq!(
+            select(
+                &prefix_range::<Key, _>((
+                    Bound::Excluded((1u8, 10u16)),
+                    Bound::Excluded((2u8, 15u16))
+                ))
+                .unwrap()
+            ),
+            filter(|key| (1, 10) < (key.0, key.1) && (key.0, key.1) < (2, 15))
+        );
+        assert_eq!(
+            select(&prefix::<Key, _>(&(0xFFu8,)).unwrap()),
+            filter(|key| key.0 == 0xFF)
+        );
+        assert_eq!(
+            select(&prefix_range::<Key, _>((0xFEu8,)..).unwrap()),
+            filter(|key| key.0 >= 0xFE)
+        );
+        assert_eq!(
+            select(
+                &prefix_range::<Key, _>((Bound::Excluded((0xFFu8,)), Bound::Unbounded)).unwrap()
+            ),
+            vec![]
+        );
+        assert_debug_snapshot!(prefix::<Key, _>(&(0xFFu8,)).unwrap(), @r#"
+ByteRange {
+    start: [
+        255,
+    ],
+    end: None,
+}
+"#);
+        assert_debug_snapshot!(prefix::<Key, _>(&()).unwrap(), @r#"
+ByteRange {
+    start: [],
+    end: None,
+}
+"#);
+    }
+}
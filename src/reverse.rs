@@ -0,0 +1,141 @@
+use core::cmp::Ordering;
+
+use crate::{
+    io::{Sink, SliceReader, Source},
+    DecodeContext, LexOrd, LexOrdRef, LexOrdSer, Result,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Reverse<T>(pub T);
+
+impl<T: PartialOrd> PartialOrd for Reverse<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        other.0.partial_cmp(&self.0)
+    }
+}
+
+impl<T: Ord> Ord for Reverse<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+struct ReverseWriter<'a, W: Sink>(&'a mut W);
+
+impl<'a, W: Sink> Sink for ReverseWriter<'a, W> {
+    fn write_all(&mut self, buf: &[u8]) -> Result {
+        let mut inverted = [0u8; 64];
+        for chunk in buf.chunks(inverted.len()) {
+            for (inverted, byte) in inverted.iter_mut().zip(chunk) {
+                *inverted = !byte;
+            }
+            self.0.write_all(&inverted[..chunk.len()])?;
+        }
+        Ok(())
+    }
+}
+
+struct ReverseReader<'a, R: Source>(&'a mut R);
+
+impl<'a, R: Source> Source for ReverseReader<'a, R> {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result {
+        self.0.read_exact(buf)?;
+        for byte in buf {
+            *byte = !*byte;
+        }
+        Ok(())
+    }
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.0.peek()?.map(|byte| !byte))
+    }
+    fn context(&mut self) -> Option<&mut DecodeContext> {
+        self.0.context()
+    }
+}
+
+macro_rules! lexord_reverse {
+    ($reverse:ident) => {
+        impl<T: LexOrdSer> LexOrdSer for $reverse<T> {
+            const MAX_ENCODED_LEN: Option<usize> = T::MAX_ENCODED_LEN;
+
+            fn to_write(&self, writer: &mut impl Sink) -> Result {
+                self.0.to_write(&mut ReverseWriter(writer))
+            }
+            fn encoded_len(&self) -> usize {
+                self.0.encoded_len()
+            }
+        }
+
+        impl<T: LexOrd> LexOrd for $reverse<T> {
+            fn from_read(reader: &mut impl Source) -> Result<Self> {
+                Ok($reverse(T::from_read(&mut ReverseReader(reader))?))
+            }
+        }
+
+        impl<'de, T: LexOrd> LexOrdRef<'de> for $reverse<T> {
+            fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Self::from_read(slice)
+            }
+        }
+    };
+}
+
+lexord_reverse!(Reverse);
+
+mod std_reverse {
+    use core::cmp::Reverse;
+
+    use super::*;
+
+    lexord_reverse!(Reverse);
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use crate::{util::test::encode, LexOrdSer};
+
+    use super::Reverse;
+
+    #[test]
+    fn test_reverse_format() {
+        assert_snapshot!(encode(Reverse(())), @"");
+        assert_snapshot!(encode(Reverse(0u8)), @"FF");
+        assert_snapshot!(encode(Reverse(0xFEu8)), @"01");
+        assert_snapshot!(encode(Reverse(1u16)), @"7E");
+        assert_snapshot!(encode(Reverse("ab".to_string())), @"9E 9D FF");
+        assert_snapshot!(encode(Reverse("".to_string())), @"FF");
+        assert_snapshot!(encode(Reverse(Reverse(1u16))), @"81");
+        assert_snapshot!(encode(std::cmp::Reverse(1u16)), @"7E");
+        assert!(!crate::is_canonical::<Reverse<u16>>(b"\x3F\xFA"));
+    }
+
+    #[test]
+    fn test_reverse_seq() {
+        assert_snapshot!(encode(vec![Reverse(0xFFu8), Reverse(0xFEu8), Reverse(0u8)]), @"01 00 01 01 FF 00");
+        assert_snapshot!(encode(vec![Reverse(())]), @"01 00");
+        assert_snapshot!(encode(vec![Reverse(vec![0xFFu8])]), @"01 00 FF 00");
+        assert_snapshot!(encode((Reverse(1u8), Reverse("a".to_string()), 2u8)), @"FE 9E FF 02");
+    }
+
+    #[test]
+    fn test_reverse_order() {
+        let strings = ["", "\0", "\x01", "a", "ab", "b", "\u{FF}"];
+        let bytes = [vec![], vec![0u8], vec![1, 0xFF], vec![0xFF]];
+        let mut values = vec![];
+        for string in strings {
+            for bytes in &bytes {
+                values.push((Reverse(string.to_string()), vec![Reverse(bytes.clone())]));
+            }
+        }
+        let mut by_bytes = values.clone();
+        by_bytes.sort_by_key(|value| {
+            let mut bytes = vec![];
+            value.to_write(&mut bytes).unwrap();
+            bytes
+        });
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(values, by_bytes);
+    }
+}
@@ -1,5 +1,8 @@
+pub mod cow;
 pub mod float;
 pub mod int;
+pub mod option;
+pub mod pointer;
 pub mod string;
 pub mod tuple;
 pub mod vec;
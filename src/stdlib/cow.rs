@@ -0,0 +1,150 @@
+use alloc::{borrow::Cow, borrow::ToOwned, string::String, vec::Vec};
+
+use crate::{
+    io::{Sink, SliceReader, Source},
+    options, Error, ErrorKind, LexOrd, LexOrdRef, LexOrdSer, Result,
+};
+
+impl<B: LexOrdSer + ToOwned + ?Sized> LexOrdSer for Cow<'_, B> {
+    const MAX_ENCODED_LEN: Option<usize> = B::MAX_ENCODED_LEN;
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        B::to_write(self, writer)
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        B::to_write_seq(self, writer)
+    }
+    fn encoded_len(&self) -> usize {
+        B::encoded_len(self)
+    }
+    fn encoded_len_seq(&self) -> usize {
+        B::encoded_len_seq(self)
+    }
+}
+
+impl<B: LexOrdSer + ToOwned + ?Sized> LexOrd for Cow<'_, B>
+where
+    B::Owned: LexOrd,
+{
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
+        Ok(Cow::Owned(B::Owned::from_read(reader)?))
+    }
+    fn from_read_seq(first: u8, reader: &mut impl Source) -> Result<Self> {
+        Ok(Cow::Owned(B::Owned::from_read_seq(first, reader)?))
+    }
+}
+
+impl<'de: 'a, 'a> LexOrdRef<'de> for Cow<'a, [u8]> {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        let mut len = 0;
+        let mut count = 0;
+        let mut escaped = false;
+        // Errors consume the input up to where the byte-wise decoder would have stopped,
+        // so both report the same position and element index.
+        let input = slice.as_bytes();
+        let fail = |slice: &mut SliceReader<'de>, consumed: usize, error: Error| {
+            slice.advance(consumed.min(input.len()));
+            Err(error)
+        };
+        loop {
+            let byte = match input.get(len) {
+                None => return fail(slice, len, ErrorKind::UnexpectedEof.into()),
+                Some(0x00) => break,
+                Some(&byte) => byte,
+            };
+            count += 1;
+            if let Err(error) = options::check_elements(slice, count) {
+                return fail(slice, len, error.with_index(count - 1));
+            }
+            if byte == 0x01 {
+                if options::strict(slice) && input.get(len + 1).is_some_and(|&byte| byte > 0x01) {
+                    let error = Error::from(ErrorKind::NonCanonical).with_index(count - 1);
+                    return fail(slice, len + 2, error);
+                }
+                escaped = true;
+                len += 2;
+            } else {
+                len += 1;
+            }
+        }
+        let bytes = &input[..len];
+        slice.advance(len + 1);
+        if !escaped {
+            return Ok(Cow::Borrowed(bytes));
+        }
+        let mut unescaped = Vec::with_capacity(len);
+        let mut bytes = bytes.iter();
+        while let Some(&byte) = bytes.next() {
+            match byte {
+                0x01 => unescaped.extend(bytes.next()),
+                byte => unescaped.push(byte),
+            }
+        }
+        Ok(Cow::Owned(unescaped))
+    }
+}
+
+impl<'de: 'a, 'a> LexOrdRef<'de> for Cow<'a, str> {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Ok(match Cow::<[u8]>::from_slice(slice)? {
+            Cow::Borrowed(bytes) => Cow::Borrowed(core::str::from_utf8(bytes)?),
+            Cow::Owned(bytes) => Cow::Owned(String::from_utf8(bytes)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+
+    use crate::{from_slice, io::SliceReader, LexOrdRef};
+
+    #[test]
+    fn test_cow_str() {
+        assert!(matches!(
+            from_slice::<Cow<str>>(b"\x00"),
+            Ok(Cow::Borrowed(""))
+        ));
+        assert!(matches!(
+            from_slice::<Cow<str>>(b"abc\x00"),
+            Ok(Cow::Borrowed("abc"))
+        ));
+        assert!(
+            matches!(from_slice::<Cow<str>>(b"a\x01\x00\x00"), Ok(Cow::Owned(s)) if s == "a\0")
+        );
+        assert!(from_slice::<Cow<str>>(b"abc").is_err());
+        assert!(from_slice::<Cow<str>>(b"\xFF\x00").is_err());
+    }
+
+    #[test]
+    fn test_cow_bytes() {
+        assert!(matches!(
+            from_slice::<Cow<[u8]>>(b"\x02\x03\x00"),
+            Ok(Cow::Borrowed([2, 3]))
+        ));
+        assert!(
+            matches!(from_slice::<Cow<[u8]>>(b"\x01\x01\x02\x00"), Ok(Cow::Owned(v)) if v == [1, 2])
+        );
+    }
+
+    #[test]
+    fn test_cow_nested() {
+        let (a, b, c): (Cow<str>, u8, Vec<Cow<str>>) =
+            from_slice(b"ab\x00\x07\x01\x00cd\x00\x01\x01\x01\x00\x00").unwrap();
+        assert!(matches!(a, Cow::Borrowed("ab")));
+        assert_eq!(b, 7);
+        assert!(matches!(
+            c.as_slice(),
+            [Cow::Borrowed(""), Cow::Borrowed("cd"), Cow::Owned(_)]
+        ));
+        assert_eq!(c[2], "\x01");
+    }
+
+    #[test]
+    fn test_cow_outlived() {
+        fn decode<'a>(slice: &mut SliceReader<'static>) -> Cow<'a, str> {
+            <Cow<'a, str> as LexOrdRef<'static>>::from_slice(slice).unwrap()
+        }
+        assert_eq!(decode(&mut SliceReader::new(b"ab\x00")), "ab");
+    }
+}
@@ -1,50 +1,95 @@
-use std::io::{Read, Write};
+use crate::{
+    io::{Sink, SliceReader, Source},
+    options, write_seq_bytes, ErrorKind, LexOrd, LexOrdRef, LexOrdSer, Result,
+};
 
-use crate::{LexOrd, LexOrdSer, Result};
+fn f32_bytes(value: f32) -> [u8; 4] {
+    if value == 0.0 {
+        return [0x80, 0x00, 0x00, 0x00];
+    }
+    let mut bits = value.to_bits();
+    bits ^= 0x80000000 | (((bits as i32) >> 31) as u32);
+    bits.to_be_bytes()
+}
+
+fn f64_bytes(value: f64) -> [u8; 8] {
+    if value == 0.0 {
+        return [0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    }
+    let mut bits = value.to_bits();
+    bits ^= 0x8000000000000000 | (((bits as i64) >> 63) as u64);
+    bits.to_be_bytes()
+}
 
 impl LexOrdSer for f32 {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
-        if self == &0.0 {
-            writer.write_all(&[0x80, 0x00, 0x00, 0x00])?;
-            return Ok(());
-        }
-        let mut bits = self.to_bits();
-        bits ^= 0x80000000 | (((bits as i32) >> 31) as u32);
-        writer.write_all(&bits.to_be_bytes())?;
-        Ok(())
+    const MAX_ENCODED_LEN: Option<usize> = Some(4);
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        writer.write_all(&f32_bytes(*self))
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        write_seq_bytes(writer, &f32_bytes(*self))
+    }
+    fn encoded_len(&self) -> usize {
+        4
     }
 }
 
 impl LexOrd for f32 {
-    fn from_read(reader: &mut impl Read) -> Result<Self> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
         let mut buf = [0u8; 4];
         reader.read_exact(&mut buf)?;
         let mut bits = u32::from_be_bytes(buf);
         bits ^= 0x80000000 | ((((!bits) as i32) >> 31) as u32);
-        Ok(f32::from_bits(bits))
+        let value = f32::from_bits(bits);
+        // The encoder writes every NaN payload as is, so only a negative zero, which it
+        // writes as a positive one, has a second encoding.
+        if options::strict(reader) && value == 0.0 && value.is_sign_negative() {
+            return Err(ErrorKind::NonCanonical.into());
+        }
+        Ok(value)
+    }
+}
+
+impl<'de> LexOrdRef<'de> for f32 {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_read(slice)
     }
 }
 
 impl LexOrdSer for f64 {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
-        if self == &0.0 {
-            writer.write_all(&[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00])?;
-            return Ok(());
-        }
-        let mut bits = self.to_bits();
-        bits ^= 0x8000000000000000 | (((bits as i64) >> 63) as u64);
-        writer.write_all(&bits.to_be_bytes())?;
-        Ok(())
+    const MAX_ENCODED_LEN: Option<usize> = Some(8);
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        writer.write_all(&f64_bytes(*self))
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        write_seq_bytes(writer, &f64_bytes(*self))
+    }
+    fn encoded_len(&self) -> usize {
+        8
     }
 }
 
 impl LexOrd for f64 {
-    fn from_read(reader: &mut impl Read) -> Result<Self> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
         let mut buf = [0u8; 8];
         reader.read_exact(&mut buf)?;
         let mut bits = u64::from_be_bytes(buf);
         bits ^= 0x8000000000000000 | ((((!bits) as i64) >> 63) as u64);
-        Ok(f64::from_bits(bits))
+        let value = f64::from_bits(bits);
+        // The encoder writes every NaN payload as is, so only a negative zero, which it
+        // writes as a positive one, has a second encoding.
+        if options::strict(reader) && value == 0.0 && value.is_sign_negative() {
+            return Err(ErrorKind::NonCanonical.into());
+        }
+        Ok(value)
+    }
+}
+
+impl<'de> LexOrdRef<'de> for f64 {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_read(slice)
     }
 }
 
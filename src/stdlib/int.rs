@@ -1,62 +1,155 @@
-use std::io::{Read, Write};
+use alloc::{borrow::Cow, vec::Vec};
 
-use crate::{Error, LexOrd, LexOrdSer, Result};
+use crate::{
+    io::{Counter, Sink, SliceReader, Source},
+    options,
+    util::len::{int_len, max, uint_len},
+    write_seq_bytes, ErrorKind, LexOrd, LexOrdRef, LexOrdSer, Result,
+};
+
+fn check_varint<T: LexOrdSer>(reader: &mut impl Source, value: &T, len: usize) -> Result {
+    if !options::strict(reader) {
+        return Ok(());
+    }
+    let mut counter = Counter(0);
+    value.to_write(&mut counter)?;
+    if counter.0 != len {
+        return Err(ErrorKind::NonCanonical.into());
+    }
+    Ok(())
+}
 
 impl LexOrdSer for bool {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
+    const MAX_ENCODED_LEN: Option<usize> = Some(1);
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
         writer.write_all(&[*self as u8 + 0x80])?;
         Ok(())
     }
-    fn to_write_seq(&self, writer: &mut impl Write) -> Result {
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
         self.to_write(writer)
     }
+    fn encoded_len(&self) -> usize {
+        1
+    }
+    fn encoded_len_seq(&self) -> usize {
+        1
+    }
 }
 
 impl LexOrd for bool {
-    fn from_read(reader: &mut impl Read) -> Result<Self> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
         let mut buf = [0];
         reader.read_exact(&mut buf)?;
-        Ok(buf[0] != 0x80)
+        match buf[0] {
+            0x80 => Ok(false),
+            0x81 => Ok(true),
+            tag if options::strict(reader) => Err(ErrorKind::BadTag(tag).into()),
+            _ => Ok(true),
+        }
+    }
+    fn from_read_seq(_first: u8, reader: &mut impl Source) -> Result<Self> {
+        Self::from_read(reader)
+    }
+}
+
+impl<'de> LexOrdRef<'de> for bool {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_read(slice)
     }
-    fn from_read_seq(first: u8, reader: &mut impl Read) -> Result<Self> {
-        Self::from_read(&mut [first].chain(reader))
+    fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_slice(slice)
     }
 }
 
 impl LexOrdSer for u8 {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
+    const MAX_ENCODED_LEN: Option<usize> = Some(1);
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
         writer.write_all(&[*self])?;
         Ok(())
     }
+    fn to_write_items(items: &[Self], writer: &mut impl Sink) -> Result {
+        let mut rest = items;
+        while let Some(index) = rest.iter().position(|&byte| byte <= 0x01) {
+            writer.write_all(&rest[..index])?;
+            writer.write_all(&[0x01, rest[index]])?;
+            rest = &rest[index + 1..];
+        }
+        writer.write_all(rest)
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        write_seq_bytes(writer, &[*self])
+    }
+    fn encoded_len(&self) -> usize {
+        1
+    }
+    fn encoded_len_seq(&self) -> usize {
+        1 + (*self <= 0x01) as usize
+    }
 }
 
 impl LexOrd for u8 {
-    fn from_read(reader: &mut impl Read) -> Result<Self> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
         let mut buf = [0];
         reader.read_exact(&mut buf)?;
         Ok(buf[0])
     }
 }
 
+impl<'de> LexOrdRef<'de> for u8 {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_read(slice)
+    }
+    fn from_slice_items(slice: &mut SliceReader<'de>) -> Result<Vec<Self>> {
+        // The bytes are one level deeper than the sequence, as in the element-wise path.
+        if slice.as_bytes().first().is_some_and(|&byte| byte != 0x00) {
+            options::check_depth(slice).map_err(|error| error.with_index(0))?;
+        }
+        Ok(Cow::<[u8]>::from_slice(slice)?.into_owned())
+    }
+}
+
 impl LexOrdSer for i8 {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
+    const MAX_ENCODED_LEN: Option<usize> = Some(1);
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
         writer.write_all(&[(self ^ i8::MIN) as u8])?;
         Ok(())
     }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        write_seq_bytes(writer, &[(self ^ i8::MIN) as u8])
+    }
+    fn encoded_len(&self) -> usize {
+        1
+    }
 }
 
 impl LexOrd for i8 {
-    fn from_read(reader: &mut impl Read) -> Result<Self> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
         let mut buf = [0];
         reader.read_exact(&mut buf)?;
         Ok(buf[0] as i8 ^ i8::MIN)
     }
 }
 
+impl<'de> LexOrdRef<'de> for i8 {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_read(slice)
+    }
+}
+
+// Wider integers are varints of 1, 2, 4, 8 or 17 bytes, with the length in the
+// leading bits of the first byte. Those bits cost the top of each size, so
+// `MAX_ENCODED_LEN` is the next size up from the type's width: 4 for `u16`, 8 for
+// `u32` and 17 for `u64`. Only `u8` and `i8` are written as a single raw byte, which
+// makes `(u16, i8)` at most 5 bytes.
 macro_rules! lexord_uint {
     ($t:ty) => {
         impl LexOrdSer for $t {
-            fn to_write(&self, writer: &mut impl Write) -> Result {
+            const MAX_ENCODED_LEN: Option<usize> = Some(uint_len(<$t>::MAX as u128));
+
+            fn to_write(&self, writer: &mut impl Sink) -> Result {
                 match *self as u128 {
                     0..=0x3F => writer.write_all(&[*self as u8 | 0x80])?,
                     0x40..=0x1FFF => writer.write_all(&(*self as u16 | 0xC000).to_be_bytes())?,
@@ -73,40 +166,65 @@ macro_rules! lexord_uint {
                 }
                 Ok(())
             }
-            fn to_write_seq(&self, writer: &mut impl Write) -> Result {
+            fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
                 self.to_write(writer)
             }
+            fn encoded_len(&self) -> usize {
+                uint_len(*self as u128)
+            }
+            fn encoded_len_seq(&self) -> usize {
+                self.encoded_len()
+            }
         }
         impl LexOrd for $t {
-            fn from_read(reader: &mut impl Read) -> Result<Self> {
+            fn from_read(reader: &mut impl Source) -> Result<Self> {
                 let mut buf = [0u8; 16];
                 reader.read_exact(&mut buf[..1])?;
-                match buf[0] {
-                    0x80..=0xBF => Ok((buf[0] & !0x80) as $t),
+                let (value, len): ($t, usize) = match buf[0] {
+                    0x80..=0xBF => ((buf[0] & !0x80) as $t, 1),
                     0xC0..=0xDF => {
                         buf[0] &= !0xC0;
                         reader.read_exact(&mut buf[1..2])?;
-                        Ok(u16::from_be_bytes(buf[..2].try_into().unwrap()).try_into()?)
+                        (
+                            u16::from_be_bytes(buf[..2].try_into().unwrap()).try_into()?,
+                            2,
+                        )
                     }
                     0xE0..=0xEF => {
                         buf[0] &= !0xE0;
                         reader.read_exact(&mut buf[1..4])?;
-                        Ok(u32::from_be_bytes(buf[..4].try_into().unwrap()).try_into()?)
+                        (
+                            u32::from_be_bytes(buf[..4].try_into().unwrap()).try_into()?,
+                            4,
+                        )
                     }
                     0xF0..=0xF7 => {
                         buf[0] &= !0xF0;
                         reader.read_exact(&mut buf[1..8])?;
-                        Ok(u64::from_be_bytes(buf[..8].try_into().unwrap()).try_into()?)
+                        (
+                            u64::from_be_bytes(buf[..8].try_into().unwrap()).try_into()?,
+                            8,
+                        )
                     }
                     0xF8 => {
                         reader.read_exact(&mut buf)?;
-                        Ok(u128::from_be_bytes(buf).try_into()?)
+                        (u128::from_be_bytes(buf).try_into()?, 17)
                     }
-                    _ => Err(Error::Parse(format!("Unsupported VarInt prefix: {buf:?}"))),
-                }
+                    _ => return Err(ErrorKind::BadVarIntPrefix(buf[0]).into()),
+                };
+                check_varint(reader, &value, len)?;
+                Ok(value)
             }
-            fn from_read_seq(first: u8, reader: &mut impl Read) -> Result<Self> {
-                Self::from_read(&mut [first].chain(reader))
+            fn from_read_seq(_first: u8, reader: &mut impl Source) -> Result<Self> {
+                Self::from_read(reader)
+            }
+        }
+        impl<'de> LexOrdRef<'de> for $t {
+            fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Self::from_read(slice)
+            }
+            fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Self::from_slice(slice)
             }
         }
     };
@@ -121,7 +239,12 @@ lexord_uint!(usize);
 macro_rules! lexord_int {
     ($t:ty) => {
         impl LexOrdSer for $t {
-            fn to_write(&self, writer: &mut impl Write) -> Result {
+            const MAX_ENCODED_LEN: Option<usize> = max(
+                Some(int_len(<$t>::MIN as i128)),
+                Some(int_len(<$t>::MAX as i128)),
+            );
+
+            fn to_write(&self, writer: &mut impl Sink) -> Result {
                 match *self as i128 {
                     0..=i128::MAX => (*self as u128).to_write(writer)?,
                     -0x40..=-0x01 => writer.write_all(&(*self as i8 & 0x7F).to_be_bytes())?,
@@ -139,60 +262,94 @@ macro_rules! lexord_int {
                 }
                 Ok(())
             }
-            fn to_write_seq(&self, writer: &mut impl Write) -> Result {
+            fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
                 self.to_write(writer)
             }
+            fn encoded_len(&self) -> usize {
+                int_len(*self as i128)
+            }
+            fn encoded_len_seq(&self) -> usize {
+                self.encoded_len()
+            }
         }
         impl LexOrd for $t {
-            fn from_read(reader: &mut impl Read) -> Result<Self> {
+            fn from_read(reader: &mut impl Source) -> Result<Self> {
                 let mut buf = [0u8; 16];
                 reader.read_exact(&mut buf[..1])?;
-                match buf[0] {
-                    0x80..=0xBF => Ok((buf[0] & !0x80) as $t),
+                let (value, len): ($t, usize) = match buf[0] {
+                    0x80..=0xBF => ((buf[0] & !0x80) as $t, 1),
                     0xC0..=0xDF => {
                         buf[0] &= !0xC0;
                         reader.read_exact(&mut buf[1..2])?;
-                        Ok(u16::from_be_bytes(buf[..2].try_into().unwrap()).try_into()?)
+                        (
+                            u16::from_be_bytes(buf[..2].try_into().unwrap()).try_into()?,
+                            2,
+                        )
                     }
                     0xE0..=0xEF => {
                         buf[0] &= !0xE0;
                         reader.read_exact(&mut buf[1..4])?;
-                        Ok(u32::from_be_bytes(buf[..4].try_into().unwrap()).try_into()?)
+                        (
+                            u32::from_be_bytes(buf[..4].try_into().unwrap()).try_into()?,
+                            4,
+                        )
                     }
                     0xF0..=0xF7 => {
                         buf[0] &= !0xF0;
                         reader.read_exact(&mut buf[1..8])?;
-                        Ok(u64::from_be_bytes(buf[..8].try_into().unwrap()).try_into()?)
+                        (
+                            u64::from_be_bytes(buf[..8].try_into().unwrap()).try_into()?,
+                            8,
+                        )
                     }
                     0xF8 => {
                         reader.read_exact(&mut buf)?;
-                        Ok(u128::from_be_bytes(buf).try_into()?)
+                        (u128::from_be_bytes(buf).try_into()?, 17)
                     }
-                    0x40..=0x7F => Ok((buf[0] | 0x80) as i8 as $t),
+                    0x40..=0x7F => ((buf[0] | 0x80) as i8 as $t, 1),
                     0x20..=0x3F => {
                         buf[0] |= 0xC0;
                         reader.read_exact(&mut buf[1..2])?;
-                        Ok(i16::from_be_bytes(buf[..2].try_into().unwrap()).try_into()?)
+                        (
+                            i16::from_be_bytes(buf[..2].try_into().unwrap()).try_into()?,
+                            2,
+                        )
                     }
                     0x10..=0x1F => {
                         buf[0] |= 0xE0;
                         reader.read_exact(&mut buf[1..4])?;
-                        Ok(i32::from_be_bytes(buf[..4].try_into().unwrap()).try_into()?)
+                        (
+                            i32::from_be_bytes(buf[..4].try_into().unwrap()).try_into()?,
+                            4,
+                        )
                     }
                     0x08..=0x0F => {
                         buf[0] |= 0xF0;
                         reader.read_exact(&mut buf[1..8])?;
-                        Ok(i64::from_be_bytes(buf[..8].try_into().unwrap()).try_into()?)
+                        (
+                            i64::from_be_bytes(buf[..8].try_into().unwrap()).try_into()?,
+                            8,
+                        )
                     }
                     0x04 => {
                         reader.read_exact(&mut buf)?;
-                        Ok(i128::from_be_bytes(buf).try_into()?)
+                        (i128::from_be_bytes(buf).try_into()?, 17)
                     }
-                    _ => Err(Error::Parse(format!("Unsupported VarInt prefix: {buf:?}"))),
-                }
+                    _ => return Err(ErrorKind::BadVarIntPrefix(buf[0]).into()),
+                };
+                check_varint(reader, &value, len)?;
+                Ok(value)
             }
-            fn from_read_seq(first: u8, reader: &mut impl Read) -> Result<Self> {
-                Self::from_read(&mut [first].chain(reader))
+            fn from_read_seq(_first: u8, reader: &mut impl Source) -> Result<Self> {
+                Self::from_read(reader)
+            }
+        }
+        impl<'de> LexOrdRef<'de> for $t {
+            fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Self::from_read(slice)
+            }
+            fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Self::from_slice(slice)
             }
         }
     };
@@ -208,7 +365,7 @@ lexord_int!(isize);
 mod tests {
     use insta::assert_snapshot;
 
-    use crate::util::test::encode;
+    use crate::{util::test::encode, LexOrdSer};
 
     #[test]
     fn test_u8() {
@@ -275,4 +432,19 @@ mod tests {
         assert_snapshot!(encode_varint(i128::MIN),
                          @"04 80 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00");
     }
+
+    #[test]
+    fn test_max_encoded_len() {
+        assert_eq!(u8::MAX_ENCODED_LEN, Some(1));
+        assert_eq!(u16::MAX_ENCODED_LEN, Some(4));
+        assert_eq!(u32::MAX_ENCODED_LEN, Some(8));
+        assert_eq!(u64::MAX_ENCODED_LEN, Some(17));
+        assert_eq!(i16::MAX_ENCODED_LEN, Some(4));
+        assert_eq!(i32::MAX_ENCODED_LEN, Some(8));
+        assert_eq!(i128::MAX_ENCODED_LEN, Some(17));
+        assert_eq!(u32::MAX.encoded_len(), 8);
+        assert_eq!(0x3Fu32.encoded_len(), 1);
+        assert_eq!((-0x10000001i32).encoded_len(), 8);
+        assert_eq!((-0x10000000i32).encoded_len(), 4);
+    }
 }
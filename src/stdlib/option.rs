@@ -0,0 +1,100 @@
+use crate::{
+    io::{Sink, SliceReader, Source},
+    util::len,
+    ErrorKind, LexOrd, LexOrdRef, LexOrdSer, Result,
+};
+
+pub(crate) fn write_option<T: LexOrdSer>(
+    value: Option<&T>,
+    tags: [u8; 2],
+    writer: &mut impl Sink,
+) -> Result {
+    match value {
+        None => writer.write_all(&[tags[0]])?,
+        Some(value) => {
+            writer.write_all(&[tags[1]])?;
+            value.to_write(writer)?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn read_option<T: LexOrd>(tags: [u8; 2], reader: &mut impl Source) -> Result<Option<T>> {
+    match u8::from_read(reader)? {
+        tag if tag == tags[0] => Ok(None),
+        tag if tag == tags[1] => Ok(Some(T::from_read(reader)?)),
+        tag => Err(ErrorKind::BadTag(tag).into()),
+    }
+}
+
+pub(crate) fn slice_option<'de, T: LexOrdRef<'de>>(
+    tags: [u8; 2],
+    slice: &mut SliceReader<'de>,
+) -> Result<Option<T>> {
+    match u8::from_slice(slice)? {
+        tag if tag == tags[0] => Ok(None),
+        tag if tag == tags[1] => Ok(Some(T::from_slice(slice)?)),
+        tag => Err(ErrorKind::BadTag(tag).into()),
+    }
+}
+
+const TAGS: [u8; 2] = [0x80, 0x81];
+
+impl<T: LexOrdSer> LexOrdSer for Option<T> {
+    const MAX_ENCODED_LEN: Option<usize> = len::sum(Some(1), T::MAX_ENCODED_LEN);
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        write_option(self.as_ref(), TAGS, writer)
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        self.to_write(writer)
+    }
+    fn encoded_len(&self) -> usize {
+        1 + self.as_ref().map_or(0, T::encoded_len)
+    }
+    fn encoded_len_seq(&self) -> usize {
+        self.encoded_len()
+    }
+}
+
+impl<T: LexOrd> LexOrd for Option<T> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
+        read_option(TAGS, reader)
+    }
+    fn from_read_seq(_first: u8, reader: &mut impl Source) -> Result<Self> {
+        Self::from_read(reader)
+    }
+}
+
+impl<'de, T: LexOrdRef<'de>> LexOrdRef<'de> for Option<T> {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        slice_option(TAGS, slice)
+    }
+    fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_slice(slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use crate::util::test::encode;
+
+    #[test]
+    fn test_option_format() {
+        assert_snapshot!(encode(None::<u8>), @"80");
+        assert_snapshot!(encode(Some(0u8)), @"81 00");
+        assert_snapshot!(encode(Some(())), @"81");
+        assert_snapshot!(encode(Some(None::<u8>)), @"81 80");
+        assert_snapshot!(encode(Some("".to_string())), @"81 00");
+        assert_snapshot!(encode(vec![None, Some(0u8), Some(5u8)]), @"80 81 00 81 05 00");
+        assert_snapshot!(encode((None::<u16>, Some(1u16))), @"80 81 81");
+    }
+
+    #[test]
+    fn test_option_invalid() {
+        assert!(crate::from_slice::<Option<u8>>(b"\x00").is_err());
+        assert!(crate::from_slice::<Option<u8>>(b"\x82\x00").is_err());
+    }
+}
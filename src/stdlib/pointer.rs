@@ -0,0 +1,111 @@
+use alloc::{boxed::Box, rc::Rc, string::String, sync::Arc, vec::Vec};
+
+use crate::{
+    io::{Sink, SliceReader, Source},
+    LexOrd, LexOrdRef, LexOrdSer, Result,
+};
+
+macro_rules! lexord_pointer {
+    ($pointer:ident) => {
+        impl<T: LexOrdSer + ?Sized> LexOrdSer for $pointer<T> {
+            // Recursive types point back to themselves, so a bound through the pointer
+            // would be a cycle in const evaluation.
+            const MAX_ENCODED_LEN: Option<usize> = None;
+
+            fn to_write(&self, writer: &mut impl Sink) -> Result {
+                T::to_write(self, writer)
+            }
+            fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+                T::to_write_seq(self, writer)
+            }
+            fn encoded_len(&self) -> usize {
+                T::encoded_len(self)
+            }
+            fn encoded_len_seq(&self) -> usize {
+                T::encoded_len_seq(self)
+            }
+        }
+
+        impl<T: LexOrd> LexOrd for $pointer<T> {
+            fn from_read(reader: &mut impl Source) -> Result<Self> {
+                Ok($pointer::new(T::from_read(reader)?))
+            }
+            fn from_read_seq(first: u8, reader: &mut impl Source) -> Result<Self> {
+                Ok($pointer::new(T::from_read_seq(first, reader)?))
+            }
+        }
+
+        impl<'de, T: LexOrdRef<'de>> LexOrdRef<'de> for $pointer<T> {
+            fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Ok($pointer::new(T::from_slice(slice)?))
+            }
+            fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Ok($pointer::new(T::from_slice_seq(slice)?))
+            }
+        }
+
+        lexord_pointer!($pointer, str, String);
+        lexord_pointer!($pointer, [T], Vec<T>, T);
+    };
+    ($pointer:ident, $unsized:ty, $owned:ty $(, $param:ident)?) => {
+        impl$(<$param: LexOrd>)? LexOrd for $pointer<$unsized> {
+            fn from_read(reader: &mut impl Source) -> Result<Self> {
+                Ok(<$owned>::from_read(reader)?.into())
+            }
+            fn from_read_seq(first: u8, reader: &mut impl Source) -> Result<Self> {
+                Ok(<$owned>::from_read_seq(first, reader)?.into())
+            }
+        }
+
+        impl<'de, $($param: LexOrdRef<'de>)?> LexOrdRef<'de> for $pointer<$unsized> {
+            fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Ok(<$owned>::from_slice(slice)?.into())
+            }
+            fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+                Ok(<$owned>::from_slice_seq(slice)?.into())
+            }
+        }
+    };
+}
+
+lexord_pointer!(Box);
+lexord_pointer!(Rc);
+lexord_pointer!(Arc);
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+    use insta::assert_snapshot;
+
+    use crate::{from_reader, to_vec, util::test::encode};
+
+    #[test]
+    fn test_pointer_format() {
+        assert_snapshot!(encode(Box::new(1u16)), @"81");
+        assert_snapshot!(encode(Rc::new("a\0".to_string())), @"61 01 00 00");
+        assert_snapshot!(encode(Arc::new(vec![0u8])), @"01 00 00");
+        assert_snapshot!(encode(Box::<str>::from("ab")), @"61 62 00");
+        assert_snapshot!(encode(Arc::<str>::from("")), @"00");
+        assert_snapshot!(encode(Rc::<[u16]>::from([1, 2])), @"81 82 00");
+        assert_snapshot!(encode(vec![Box::<[u8]>::from([0]), Box::from([])]), @"01 01 00 00 01 00 00");
+        assert_snapshot!(encode((Arc::<str>::from("a"), Box::new(2u8))), @"61 00 02");
+    }
+
+    #[test]
+    fn test_pointer_matches_pointee() {
+        for value in ["", "\0", "\x01a", "ab"] {
+            let expected = to_vec(&(value.to_string(), vec![value.to_string()])).unwrap();
+            assert_eq!(
+                to_vec(&(Box::<str>::from(value), vec![Arc::<str>::from(value)])).unwrap(),
+                expected
+            );
+            assert_eq!(
+                to_vec(&(Cow::Borrowed(value), vec![Rc::<str>::from(value)])).unwrap(),
+                expected
+            );
+        }
+        let cow: Cow<str> = from_reader(b"\x01\0x\0".as_slice()).unwrap();
+        assert_eq!(cow, "\0x");
+    }
+}
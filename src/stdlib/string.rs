@@ -1,25 +1,56 @@
-use std::io::{Read, Write};
+use alloc::{borrow::Cow, string::String, vec::Vec};
 
-use crate::{LexOrd, LexOrdSer, Result};
+use crate::{
+    io::{Sink, SliceReader, Source},
+    LexOrd, LexOrdRef, LexOrdSer, Result,
+};
 
 impl LexOrdSer for str {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
         self.as_bytes().to_write(writer)
     }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        if self.as_bytes().first().is_none_or(|&byte| byte <= 0x01) {
+            writer.write_all(&[0x01])?;
+        }
+        self.to_write(writer)
+    }
+    fn encoded_len(&self) -> usize {
+        self.as_bytes().encoded_len()
+    }
+    fn encoded_len_seq(&self) -> usize {
+        let escape = self.as_bytes().first().is_none_or(|&byte| byte <= 0x01);
+        self.encoded_len() + escape as usize
+    }
 }
 
 impl LexOrdSer for String {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
-        self.as_bytes().to_write(writer)
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        self.as_str().to_write(writer)
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        self.as_str().to_write_seq(writer)
+    }
+    fn encoded_len(&self) -> usize {
+        self.as_str().encoded_len()
+    }
+    fn encoded_len_seq(&self) -> usize {
+        self.as_str().encoded_len_seq()
     }
 }
 
 impl LexOrd for String {
-    fn from_read(reader: &mut impl Read) -> Result<Self> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
         Ok(String::from_utf8(Vec::<u8>::from_read(reader)?)?)
     }
 }
 
+impl<'de> LexOrdRef<'de> for String {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Ok(Cow::<str>::from_slice(slice)?.into_owned())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_snapshot;
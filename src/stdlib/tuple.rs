@@ -1,17 +1,38 @@
-use std::io::{Read, Write};
-
 use lexord_derive::gen_lexord_for_tuples;
 
-use crate::{LexOrd, LexOrdSer, Result};
+use crate::{
+    io::{Sink, SliceReader, Source},
+    util::{len, nested},
+    BytesPrefix, LexOrd, LexOrdRef, LexOrdSer, PrefixOf, Result, StrPrefix,
+};
 
 impl LexOrdSer for () {
-    fn to_write(&self, _writer: &mut impl Write) -> Result {
+    const MAX_ENCODED_LEN: Option<usize> = Some(0);
+
+    fn to_write(&self, _writer: &mut impl Sink) -> Result {
         Ok(())
     }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        writer.write_all(&[0x01])
+    }
+    fn encoded_len(&self) -> usize {
+        0
+    }
+    fn encoded_len_seq(&self) -> usize {
+        1
+    }
 }
 
 impl LexOrd for () {
-    fn from_read(_reader: &mut impl Read) -> Result<Self> {
+    fn from_read(_reader: &mut impl Source) -> Result<Self> {
+        Ok(())
+    }
+}
+
+impl PrefixOf<()> for () {}
+
+impl<'de> LexOrdRef<'de> for () {
+    fn from_slice(_slice: &mut SliceReader<'de>) -> Result<Self> {
         Ok(())
     }
 }
@@ -22,7 +43,7 @@ gen_lexord_for_tuples!();
 mod tests {
     use insta::assert_snapshot;
 
-    use crate::util::test::encode;
+    use crate::{to_vec, to_vec_prefix, util::test::encode, LexOrdSer};
 
     #[test]
     fn test_tuple() {
@@ -31,4 +52,27 @@ mod tests {
         assert_snapshot!(encode(((), ((), 1u8, ()), ((), (), ()), 2u8, 3u8)), @"01 02 03");
         assert_snapshot!(encode(("abc".to_string(), 2u8, 3u8)), @"61 62 63 00 02 03");
     }
+
+    #[test]
+    fn test_tuple_max_encoded_len() {
+        assert_eq!(<()>::MAX_ENCODED_LEN, Some(0));
+        assert_eq!(<(u16, i8)>::MAX_ENCODED_LEN, Some(5));
+        assert_eq!(<(u8, Option<f32>)>::MAX_ENCODED_LEN, Some(6));
+        assert_eq!(<(u8, Vec<u8>)>::MAX_ENCODED_LEN, None);
+        assert_eq!(<([u16; 3], bool)>::MAX_ENCODED_LEN, Some(13));
+        assert_eq!(<[String; 0]>::MAX_ENCODED_LEN, Some(0));
+        assert_eq!(("ab".to_string(), 1u16).encoded_len(), 4);
+    }
+
+    #[test]
+    fn test_tuple_prefix() {
+        type Key = (u64, u64, String, i64);
+        let key: Key = (1, 2, "name".to_string(), -3);
+        let full = to_vec(&key).unwrap();
+        let prefix = to_vec_prefix::<Key, _>(&(1u64, 2u64)).unwrap();
+        assert_snapshot!(encode((1u64, 2u64)), @"81 82");
+        assert!(full.starts_with(&prefix));
+        assert!(to_vec_prefix::<Key, _>(&()).unwrap().is_empty());
+        assert_eq!(to_vec_prefix::<Key, _>(&key).unwrap(), full);
+    }
 }
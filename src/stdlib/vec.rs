@@ -1,51 +1,159 @@
-use std::io::{Read, Write};
+use alloc::vec::Vec;
 
 use crate::{
-    util::iterator::{write_iterator, write_seq_iterator, ReadIter},
-    LexOrd, LexOrdSer, Result,
+    io::{Sink, SliceReader, Source},
+    options,
+    util::{iterator::ReadIter, len},
+    ErrorKind, LexOrd, LexOrdRef, LexOrdSer, Result,
 };
 
 impl<T: LexOrdSer> LexOrdSer for [T] {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
-        write_iterator(writer, &mut self.iter())
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        T::to_write_items(self, writer)?;
+        writer.write_all(&[0x00])
     }
-    fn to_write_seq(&self, writer: &mut impl Write) -> Result {
-        write_seq_iterator(writer, &mut self.iter())
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        writer.write_all(&[0x01])?;
+        self.to_write(writer)
+    }
+    fn encoded_len(&self) -> usize {
+        self.iter().map(T::encoded_len_seq).sum::<usize>() + 1
+    }
+    fn encoded_len_seq(&self) -> usize {
+        self.encoded_len() + 1
     }
 }
 
 impl<T: LexOrdSer, const N: usize> LexOrdSer for [T; N] {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
-        write_iterator(writer, &mut self.iter())
+    const MAX_ENCODED_LEN: Option<usize> = len::repeat(T::MAX_ENCODED_LEN, N);
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        for item in self {
+            item.to_write(writer)?;
+        }
+        Ok(())
+    }
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        let Some((first, rest)) = self.split_first() else {
+            return writer.write_all(&[0x01]);
+        };
+        first.to_write_seq(writer)?;
+        for item in rest {
+            item.to_write(writer)?;
+        }
+        Ok(())
+    }
+    fn encoded_len(&self) -> usize {
+        self.iter().map(T::encoded_len).sum()
+    }
+    fn encoded_len_seq(&self) -> usize {
+        match self.split_first() {
+            None => 1,
+            Some((first, rest)) => {
+                first.encoded_len_seq() + rest.iter().map(T::encoded_len).sum::<usize>()
+            }
+        }
+    }
+}
+
+fn collect_array<T, const N: usize>(iter: impl Iterator<Item = Result<T>>) -> Result<[T; N]> {
+    let items = iter
+        .enumerate()
+        .map(|(index, item)| item.map_err(|error| error.with_index(index)))
+        .collect::<Result<Vec<T>>>()?;
+    Ok(items
+        .try_into()
+        .unwrap_or_else(|_| unreachable!("exactly N items are read")))
+}
+
+impl<T: LexOrd, const N: usize> LexOrd for [T; N] {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
+        collect_array((0..N).map(|_| T::from_read(reader)))
+    }
+    fn from_read_seq(first: u8, reader: &mut impl Source) -> Result<Self> {
+        if N == 0 {
+            if first != 0x01 && options::strict(reader) {
+                return Err(ErrorKind::NonCanonical.into());
+            }
+            reader.read_exact(&mut [0])?;
+            return Self::from_read(reader);
+        }
+        collect_array((0..N).map(|index| match index {
+            0 => T::from_read_seq(first, reader),
+            _ => T::from_read(reader),
+        }))
+    }
+}
+
+impl<'de, T: LexOrdRef<'de>, const N: usize> LexOrdRef<'de> for [T; N] {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        collect_array((0..N).map(|_| T::from_slice(slice)))
     }
-    fn to_write_seq(&self, writer: &mut impl Write) -> Result {
-        write_seq_iterator(writer, &mut self.iter())
+    fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+        if N == 0 {
+            match slice.as_bytes() {
+                [0x01, ..] => slice.advance(1),
+                _ if options::strict(slice) => return Err(ErrorKind::NonCanonical.into()),
+                [_, ..] => slice.advance(1),
+                [] => {}
+            }
+            return Self::from_slice(slice);
+        }
+        collect_array((0..N).map(|index| match index {
+            0 => T::from_slice_seq(slice),
+            _ => T::from_slice(slice),
+        }))
     }
 }
 
 impl<T: LexOrdSer> LexOrdSer for Vec<T> {
-    fn to_write(&self, writer: &mut impl Write) -> Result {
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
         self.as_slice().to_write(writer)
     }
-    fn to_write_seq(&self, writer: &mut impl Write) -> Result {
-        write_seq_iterator(writer, &mut self.iter())
+    fn to_write_seq(&self, writer: &mut impl Sink) -> Result {
+        self.as_slice().to_write_seq(writer)
+    }
+    fn encoded_len(&self) -> usize {
+        self.as_slice().encoded_len()
+    }
+    fn encoded_len_seq(&self) -> usize {
+        self.as_slice().encoded_len_seq()
     }
 }
 
 impl<T: LexOrd> LexOrd for Vec<T> {
-    fn from_read(reader: &mut impl Read) -> Result<Self> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
         ReadIter::new(reader).collect()
     }
-    fn from_read_seq(first: u8, reader: &mut impl Read) -> Result<Self> {
+    fn from_read_seq(first: u8, reader: &mut impl Source) -> Result<Self> {
+        reader.read_exact(&mut [0])?;
         ReadIter::new_seq(first, reader)?.collect()
     }
 }
 
+impl<'de, T: LexOrdRef<'de>> LexOrdRef<'de> for Vec<T> {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        T::from_slice_items(slice)
+    }
+    fn from_slice_seq(slice: &mut SliceReader<'de>) -> Result<Self> {
+        match slice.as_bytes() {
+            [0x01, ..] => slice.advance(1),
+            [first, ..] => return Err(ErrorKind::BadTag(*first).into()),
+            [] => return Err(ErrorKind::UnexpectedEof.into()),
+        }
+        Self::from_slice(slice)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_snapshot;
 
-    use crate::util::test::encode;
+    use crate::{
+        from_slice, to_vec,
+        util::{iterator::write_seq_iterator, test::encode},
+        LexOrdSer,
+    };
 
     #[test]
     fn test_vec_format() {
@@ -56,4 +164,41 @@ mod tests {
         assert_snapshot!(encode(vec![0u8, 1u8, 2u8, 3u8]), @"01 00 01 01 02 03 00");
         assert_snapshot!(encode(vec![0u16, 1u16, 2u16, 3u16]), @"80 81 82 83 00");
     }
+
+    #[test]
+    fn test_array_format() {
+        assert_snapshot!(encode([0u8; 0]), @"");
+        assert_snapshot!(encode([0u8, 1u8, 2u8, 3u8]), @"00 01 02 03");
+        assert_snapshot!(encode([0xABu8; 16]), @"AB AB AB AB AB AB AB AB AB AB AB AB AB AB AB AB");
+        assert_snapshot!(encode([0u16, 1u16]), @"80 81");
+        assert_snapshot!(encode(["a".to_string(), "".to_string()]), @"61 00 00");
+        assert_snapshot!(encode(vec![[0u8; 0]]), @"01 00");
+        assert_snapshot!(encode(vec![[0u8, 1u8], [2u8, 0u8]]), @"01 00 01 02 00 00");
+        assert_snapshot!(encode(([1u8, 2u8], 3u8)), @"01 02 03");
+    }
+
+    #[test]
+    fn test_bytes_fast_path() {
+        let bytes = vec![0u8, 1, 2, 0, 0, 0xFF, 1];
+        let generic: Vec<(u8,)> = bytes.iter().map(|&byte| (byte,)).collect();
+        assert_eq!(to_vec(&bytes).unwrap(), to_vec(&generic).unwrap());
+        assert_eq!(
+            to_vec(&vec![bytes.clone()]).unwrap(),
+            to_vec(&vec![generic.clone()]).unwrap()
+        );
+        let encoded = to_vec(&vec![bytes.clone()]).unwrap();
+        assert_eq!(from_slice::<Vec<Vec<u8>>>(&encoded).unwrap(), vec![bytes]);
+        assert_snapshot!(encode(vec![vec![2u8, 0u8], vec![]]), @"01 02 01 00 00 01 00 00");
+        assert_snapshot!(encode(vec!["".to_string(), "\x01".to_string(), "a".to_string()]), @"01 00 01 01 01 00 61 00 00");
+    }
+
+    #[test]
+    fn test_write_iterator() {
+        let values = vec![0u16, 1, 2];
+        let mut bytes = vec![];
+        write_seq_iterator(&mut bytes, &mut values.iter()).unwrap();
+        let mut seq_bytes = vec![];
+        values.to_write_seq(&mut seq_bytes).unwrap();
+        assert_eq!(bytes, seq_bytes);
+    }
 }
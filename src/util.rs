@@ -1,2 +1,7 @@
 pub mod iterator;
+pub mod len;
 pub mod test;
+pub mod trailing;
+pub mod with;
+
+pub use crate::options::nested;
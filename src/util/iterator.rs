@@ -1,47 +1,99 @@
-use std::{
-    io::{Read, Write},
-    marker::PhantomData,
-};
+use core::marker::PhantomData;
 
-use crate::{LexOrd, LexOrdSer, Result};
+use crate::{
+    io::{Sink, SliceReader, Source},
+    options::{self, nested},
+    ErrorKind, LexOrd, LexOrdRef, LexOrdSer, Result,
+};
 
-pub struct ReadIter<'a, R: Read, T: LexOrd> {
+pub struct ReadIter<'a, R: Source, T: LexOrd> {
     reader: &'a mut R,
+    index: usize,
     _phantom: PhantomData<T>,
 }
 
-impl<'a, R: Read, T: LexOrd> ReadIter<'a, R, T> {
+impl<'a, R: Source, T: LexOrd> ReadIter<'a, R, T> {
     pub fn new(reader: &'a mut R) -> ReadIter<'a, R, T> {
         ReadIter {
             reader,
+            index: 0,
             _phantom: PhantomData,
         }
     }
     pub fn new_seq(first: u8, reader: &'a mut R) -> Result<ReadIter<'a, R, T>> {
-        debug_assert_eq!(first, 0x01);
+        if first != 0x01 {
+            return Err(ErrorKind::BadTag(first).into());
+        }
         Ok(ReadIter {
             reader,
+            index: 0,
             _phantom: PhantomData,
         })
     }
 }
 
-impl<'a, R: Read, T: LexOrd> Iterator for ReadIter<'a, R, T> {
+impl<'a, R: Source, T: LexOrd> Iterator for ReadIter<'a, R, T> {
     type Item = Result<T>;
     fn next(&mut self) -> Option<Self::Item> {
-        let mut first = [0];
-        if let Err(error) = self.reader.read_exact(&mut first) {
-            return Some(Err(error.into()));
+        match self.reader.peek() {
+            Err(error) => Some(Err(error)),
+            Ok(None) => Some(Err(ErrorKind::UnexpectedEof.into())),
+            Ok(Some(0x00)) => self.reader.read_exact(&mut [0]).err().map(Err),
+            Ok(Some(first)) => {
+                let index = self.index;
+                self.index += 1;
+                Some(
+                    options::check_elements(self.reader, self.index)
+                        .and_then(|()| {
+                            nested(self.reader, |reader| T::from_read_seq(first, reader))
+                        })
+                        .map_err(|error| error.with_index(index)),
+                )
+            }
+        }
+    }
+}
+
+pub struct SliceIter<'a, 'de, T: LexOrdRef<'de>> {
+    slice: &'a mut SliceReader<'de>,
+    index: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, 'de, T: LexOrdRef<'de>> SliceIter<'a, 'de, T> {
+    pub fn new(slice: &'a mut SliceReader<'de>) -> SliceIter<'a, 'de, T> {
+        SliceIter {
+            slice,
+            index: 0,
+            _phantom: PhantomData,
         }
-        match first[0] {
-            0x00 => None,
-            first => Some(T::from_read_seq(first, self.reader)),
+    }
+}
+
+impl<'a, 'de, T: LexOrdRef<'de>> Iterator for SliceIter<'a, 'de, T> {
+    type Item = Result<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.slice.as_bytes().first() {
+            None => Some(Err(ErrorKind::UnexpectedEof.into())),
+            Some(0x00) => {
+                self.slice.advance(1);
+                None
+            }
+            Some(_) => {
+                let index = self.index;
+                self.index += 1;
+                Some(
+                    options::check_elements(self.slice, self.index)
+                        .and_then(|()| nested(self.slice, T::from_slice_seq))
+                        .map_err(|error| error.with_index(index)),
+                )
+            }
         }
     }
 }
 
 pub fn write_iterator<'a, T: LexOrdSer + 'a>(
-    writer: &mut impl Write,
+    writer: &mut impl Sink,
     iter: &mut impl Iterator<Item = &'a T>,
 ) -> Result {
     for item in iter {
@@ -52,7 +104,7 @@ pub fn write_iterator<'a, T: LexOrdSer + 'a>(
 }
 
 pub fn write_seq_iterator<'a, T: LexOrdSer + 'a>(
-    writer: &mut impl Write,
+    writer: &mut impl Sink,
     iter: &mut impl Iterator<Item = &'a T>,
 ) -> Result {
     writer.write_all(&[0x01])?;
@@ -0,0 +1,43 @@
+pub const fn uint_len(value: u128) -> usize {
+    match value {
+        0..=0x3F => 1,
+        0x40..=0x1FFF => 2,
+        0x2000..=0x0FFFFFFF => 4,
+        0x10000000..=0x07FFFFFFFFFFFFFF => 8,
+        _ => 17,
+    }
+}
+
+pub const fn int_len(value: i128) -> usize {
+    match value {
+        0..=i128::MAX => uint_len(value as u128),
+        -0x40..=-0x01 => 1,
+        -0x2000..=-0x41 => 2,
+        -0x10000000..=-0x2001 => 4,
+        -0x0800000000000000..=-0x10000001 => 8,
+        i128::MIN..=-0x0800000000000001 => 17,
+    }
+}
+
+pub const fn sum(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.checked_add(b),
+        _ => None,
+    }
+}
+
+pub const fn max(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) if a > b => Some(a),
+        (Some(_), Some(b)) => Some(b),
+        _ => None,
+    }
+}
+
+pub const fn repeat(a: Option<usize>, count: usize) -> Option<usize> {
+    match a {
+        Some(a) => a.checked_mul(count),
+        None if count == 0 => Some(0),
+        None => None,
+    }
+}
@@ -1,16 +1,23 @@
-use std::{cmp::Ordering, fmt::Debug, io::Read};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::{any::type_name, cmp::Ordering, fmt::Debug};
 
-use crate::LexOrd;
+use crate::{
+    from_reader_with, from_slice, is_canonical, to_vec, DecodeOptions, EncodesAs, LexOrd,
+    LexOrdRef, LexOrdSer,
+};
 
-pub fn encode<T: LexOrd + Debug>(value: T) -> String {
+pub fn encode<T: LexOrd + for<'de> LexOrdRef<'de> + Debug>(value: T) -> String {
     let mut bytes = vec![];
     value.to_write(&mut bytes).unwrap();
+    assert_eq!(to_vec(&value).unwrap(), bytes);
+    assert_eq!(value.encoded_len(), bytes.len());
+    assert!(T::MAX_ENCODED_LEN.is_none_or(|max| bytes.len() <= max));
+    let mut seq_bytes = vec![];
+    value.to_write_seq(&mut seq_bytes).unwrap();
+    assert_eq!(value.encoded_len_seq(), seq_bytes.len());
     let mut bytes_read = bytes.as_slice();
     let value_from_buf = T::from_read(&mut bytes_read).unwrap();
-    assert!(
-        bytes_read.bytes().next().is_none(),
-        "Buffer is not consumed completely"
-    );
+    assert!(bytes_read.is_empty(), "Buffer is not consumed completely");
     if let Some(cmp) = value.partial_cmp(&value_from_buf) {
         assert_eq!(
             cmp,
@@ -18,9 +25,50 @@ pub fn encode<T: LexOrd + Debug>(value: T) -> String {
             "{bytes:x?} -> {value_from_buf:?} != {value:?}"
         );
     }
+    let value_from_slice = from_slice::<T>(&bytes).unwrap();
+    if let Some(cmp) = value.partial_cmp(&value_from_slice) {
+        assert_eq!(
+            cmp,
+            Ordering::Equal,
+            "{bytes:x?} -> {value_from_slice:?} != {value:?}"
+        );
+    }
+    assert!(is_canonical::<T>(&bytes), "{bytes:x?} is not canonical");
+    assert!(from_reader_with::<T>(bytes.as_slice(), DecodeOptions::strict()).is_ok());
     bytes
         .iter()
         .map(|byte| format!("{byte:02X}"))
         .collect::<Vec<String>>()
         .join(" ")
 }
+
+// Derived comparisons agree with the encoding by construction, while hand-written ones,
+// as in types deriving with `#[lexord(no_cmp)]`, rely on this to test that they do.
+pub fn check_order<T: LexOrdSer + Debug>(values: &[T]) {
+    for a in values {
+        for b in values {
+            let (a_bytes, b_bytes) = (to_vec(a).unwrap(), to_vec(b).unwrap());
+            assert_eq!(
+                a.partial_cmp(b),
+                Some(a_bytes.cmp(&b_bytes)),
+                "{a:?} ({a_bytes:x?}) vs {b:?} ({b_bytes:x?})"
+            );
+        }
+    }
+}
+
+pub fn check_encodes_as<T: LexOrd + Debug, E: EncodesAs<T> + Debug>(values: &[E]) {
+    let type_name = type_name::<T>();
+    for value in values {
+        // Bytes that decode strictly as `T` and encode back unchanged are an encoding of `T`.
+        let bytes = to_vec(value).unwrap();
+        let mut slice = bytes.as_slice();
+        let decoded = from_reader_with::<T>(&mut slice, DecodeOptions::strict())
+            .unwrap_or_else(|error| panic!("{value:?} ({bytes:x?}) is not a {type_name}: {error}"));
+        assert!(
+            slice.is_empty(),
+            "{value:?} ({bytes:x?}) is longer than {decoded:?}"
+        );
+        assert_eq!(to_vec(&decoded).unwrap(), bytes, "{value:?} vs {decoded:?}");
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{
+    io::{SliceReader, Source},
+    options, ErrorKind, LexOrd, LexOrdRef, Result,
+};
+
+pub fn from_read<T: LexOrd>(reader: &mut impl Source) -> Result<Option<T>> {
+    match reader.peek()? {
+        Some(_) => T::from_read(reader).map(Some),
+        None => missing(reader),
+    }
+}
+
+pub fn from_slice<'de, T: LexOrdRef<'de>>(slice: &mut SliceReader<'de>) -> Result<Option<T>> {
+    if slice.as_bytes().is_empty() {
+        return missing(slice);
+    }
+    T::from_slice(slice).map(Some)
+}
+
+fn missing<T>(reader: &mut impl Source) -> Result<Option<T>> {
+    // Encoding always writes every field, so a record missing one is never canonical.
+    if options::strict(reader) {
+        return Err(ErrorKind::NonCanonical.into());
+    }
+    Ok(None)
+}
@@ -0,0 +1,80 @@
+use core::{cmp::Ordering, marker::PhantomData};
+
+use crate::{
+    io::{Sink, SliceReader, Source},
+    LexOrd, LexOrdRef, LexOrdSer, LexOrdWith, Result,
+};
+
+pub struct WithRef<'a, C, T>(pub &'a T, PhantomData<C>);
+
+impl<'a, C, T> WithRef<'a, C, T> {
+    pub fn new(value: &'a T) -> Self {
+        WithRef(value, PhantomData)
+    }
+}
+
+impl<C: LexOrdWith<T>, T> PartialEq for WithRef<'_, C, T> {
+    fn eq(&self, other: &Self) -> bool {
+        C::cmp(self.0, other.0) == Ordering::Equal
+    }
+}
+
+impl<C: LexOrdWith<T>, T> PartialOrd for WithRef<'_, C, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(C::cmp(self.0, other.0))
+    }
+}
+
+impl<C: LexOrdWith<T>, T> LexOrdSer for WithRef<'_, C, T> {
+    const MAX_ENCODED_LEN: Option<usize> = C::MAX_ENCODED_LEN;
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        C::to_write(self.0, writer)
+    }
+    fn encoded_len(&self) -> usize {
+        C::encoded_len(self.0)
+    }
+}
+
+pub struct With<C, T>(pub T, PhantomData<C>);
+
+impl<C, T> With<C, T> {
+    pub fn new(value: T) -> Self {
+        With(value, PhantomData)
+    }
+}
+
+impl<C: LexOrdWith<T>, T> PartialEq for With<C, T> {
+    fn eq(&self, other: &Self) -> bool {
+        C::cmp(&self.0, &other.0) == Ordering::Equal
+    }
+}
+
+impl<C: LexOrdWith<T>, T> PartialOrd for With<C, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(C::cmp(&self.0, &other.0))
+    }
+}
+
+impl<C: LexOrdWith<T>, T> LexOrdSer for With<C, T> {
+    const MAX_ENCODED_LEN: Option<usize> = C::MAX_ENCODED_LEN;
+
+    fn to_write(&self, writer: &mut impl Sink) -> Result {
+        C::to_write(&self.0, writer)
+    }
+    fn encoded_len(&self) -> usize {
+        C::encoded_len(&self.0)
+    }
+}
+
+impl<C: LexOrdWith<T>, T> LexOrd for With<C, T> {
+    fn from_read(reader: &mut impl Source) -> Result<Self> {
+        Ok(With::new(C::from_read(reader)?))
+    }
+}
+
+impl<'de, C: LexOrdWith<T>, T> LexOrdRef<'de> for With<C, T> {
+    fn from_slice(slice: &mut SliceReader<'de>) -> Result<Self> {
+        Self::from_read(slice)
+    }
+}
@@ -1,7 +1,9 @@
+use std::ops::RangeBounds;
+
 use insta::assert_snapshot;
 
-use lexord::util::test::encode;
-use lexord::LexOrd;
+use lexord::util::test::{check_encodes_as, check_order, encode};
+use lexord::{BytesPrefix, LexOrd, LexOrdSer, StrPrefix};
 
 #[test]
 fn test_struct() {
@@ -35,3 +37,875 @@ fn test_enum() {
     assert_snapshot!(encode(E::B(1, 2)), @"81 81 82");
     assert_snapshot!(encode(E::C { a: 1, b: 2 }), @"82 81 82");
 }
+
+#[test]
+fn test_enum_tags() {
+    #[derive(LexOrd, Debug)]
+    #[lexord(reserved(1, 4))]
+    #[repr(u8)]
+    enum E {
+        #[lexord(tag = 5)]
+        C {
+            a: u16,
+        },
+        A = 2,
+        B(u16),
+        #[lexord(tag = 0)]
+        D,
+    }
+
+    assert_snapshot!(encode(E::C { a: 1 }), @"85 81");
+    assert_snapshot!(encode(E::A), @"82");
+    assert_snapshot!(encode(E::B(1)), @"83 81");
+    assert_snapshot!(encode(E::D), @"80");
+    let values = [E::D, E::A, E::B(1), E::B(2), E::C { a: 0 }];
+    for pair in values.windows(2) {
+        assert!(pair[0] < pair[1]);
+        assert!(lexord::to_vec(&pair[0]).unwrap() < lexord::to_vec(&pair[1]).unwrap());
+    }
+    let error = lexord::from_slice::<E>(b"\x84").unwrap_err();
+    assert_snapshot!(error, @"Unexpected enum variant: 4 after 1 bytes in E");
+}
+
+#[test]
+fn test_desc_field() {
+    #[derive(LexOrd, Debug, Clone)]
+    struct Event {
+        stream: u64,
+        #[lexord(desc)]
+        name: String,
+        #[lexord(desc)]
+        ts: i64,
+    }
+
+    #[derive(LexOrd, Debug)]
+    enum E {
+        A(#[lexord(desc)] Vec<u8>, u8),
+    }
+
+    assert_snapshot!(encode(Event { stream: 1, name: "ab".to_string(), ts: 1 }), @"81 9E 9D FF 7E");
+    assert_snapshot!(encode(E::A(vec![0, 1], 2)), @"80 FE FF FE FE FF 02");
+    let mut events = vec![];
+    for stream in [1, 2] {
+        for name in ["", "\0", "a", "ab", "b"] {
+            for ts in [-1, 0, 1000] {
+                events.push(Event {
+                    stream,
+                    name: name.to_string(),
+                    ts,
+                });
+            }
+        }
+    }
+    let mut by_bytes = events.clone();
+    by_bytes.sort_by_key(|event| lexord::to_vec(event).unwrap());
+    events.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(events, by_bytes);
+    assert_eq!(events[0].name, "b");
+    assert_eq!(events[0].ts, 1000);
+    let mut values = vec![E::A(vec![], 1), E::A(vec![0], 0), E::A(vec![0, 1], 0)];
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(
+        values,
+        [E::A(vec![0, 1], 0), E::A(vec![0], 0), E::A(vec![], 1)]
+    );
+    let prefix =
+        lexord::to_vec_prefix::<Event, _>(&(1u64, lexord::Reverse("ab".to_string()))).unwrap();
+    for event in &events {
+        let matches = event.stream == 1 && event.name == "ab";
+        assert_eq!(lexord::to_vec(event).unwrap().starts_with(&prefix), matches);
+    }
+}
+
+#[test]
+fn test_skip_field() {
+    fn unknown() -> String {
+        "unknown".to_string()
+    }
+
+    #[derive(LexOrd, Debug)]
+    struct Key {
+        #[lexord(skip)]
+        cached_hash: u64,
+        id: u16,
+        #[lexord(skip, default = unknown)]
+        label: String,
+    }
+
+    #[derive(LexOrd, Debug)]
+    enum E {
+        A(#[lexord(skip)] u8, u8),
+    }
+
+    let key = Key {
+        cached_hash: 42,
+        id: 1,
+        label: "one".to_string(),
+    };
+    assert_snapshot!(encode(vec![Key { cached_hash: 0, id: 2, label: String::new() }]), @"82 00");
+    let decoded = lexord::from_slice::<Key>(&lexord::to_vec(&key).unwrap()).unwrap();
+    assert_eq!(
+        (decoded.cached_hash, decoded.id, decoded.label.as_str()),
+        (0, 1, "unknown")
+    );
+    assert_eq!(decoded, key);
+    assert_snapshot!(encode(E::A(5, 1)), @"80 01");
+    assert!(E::A(5, 1) == E::A(6, 1));
+    let E::A(skipped, _) = lexord::from_slice::<E>(b"\x80\x01").unwrap();
+    assert_eq!(skipped, 0);
+}
+
+#[test]
+fn test_trailing_fields() {
+    #[derive(LexOrd, Debug)]
+    struct V1 {
+        id: u16,
+        name: String,
+    }
+
+    #[derive(LexOrd, Debug)]
+    struct V2 {
+        id: u16,
+        name: String,
+        #[lexord(added_in = 2)]
+        tags: Vec<String>,
+        #[lexord(added_in = 3, default = default_score)]
+        score: i32,
+    }
+
+    fn default_score() -> i32 {
+        -1
+    }
+
+    let old = lexord::to_vec(&V1 {
+        id: 7,
+        name: "a".to_string(),
+    })
+    .unwrap();
+    let value = lexord::from_slice::<V2>(&old).unwrap();
+    assert_eq!((value.tags.len(), value.score), (0, -1));
+    let value = lexord::from_reader::<V2>(old.as_slice()).unwrap();
+    assert_eq!((value.tags.len(), value.score), (0, -1));
+    let mut partial = old.clone();
+    partial.extend(lexord::to_vec(&vec!["x".to_string()]).unwrap());
+    let value = lexord::from_slice::<V2>(&partial).unwrap();
+    assert_eq!((value.tags, value.score), (vec!["x".to_string()], -1));
+    assert!(!lexord::is_canonical::<V2>(&old));
+    let error = lexord::from_slice::<V2>(&old[..old.len() - 1]).unwrap_err();
+    assert_snapshot!(error, @"Unexpected end of input after 2 bytes in V2.name");
+    assert_snapshot!(encode(V2 { id: 7, name: "a".to_string(), tags: vec![], score: 2 }), @"87 61 00 00 82");
+    assert_snapshot!(encode(vec![V2 { id: 7, name: "a".to_string(), tags: vec![], score: 2 }]), @"87 61 00 00 82 00");
+}
+
+#[test]
+fn test_empty_types() {
+    #[derive(LexOrd, Debug)]
+    struct Marker;
+
+    #[derive(LexOrd, Debug)]
+    struct Empty {}
+
+    #[derive(LexOrd, Debug)]
+    struct Cached(#[lexord(skip)] u32);
+
+    #[derive(LexOrd, Debug)]
+    enum Never {}
+
+    assert_snapshot!(encode(Marker), @"");
+    assert_snapshot!(encode(Empty {}), @"");
+    assert_snapshot!(encode(vec![Marker, Marker]), @"01 01 00");
+    assert_snapshot!(encode((Empty {}, 1u8)), @"01");
+    assert_snapshot!(encode(vec![Cached(1)]), @"01 00");
+    assert_eq!(Marker::MAX_ENCODED_LEN, Some(0));
+    assert_eq!(Never::MAX_ENCODED_LEN, Some(0));
+    let error = lexord::from_slice::<Never>(b"\x80").unwrap_err();
+    assert_snapshot!(error, @"Unexpected enum variant: 0 after 1 bytes in Never");
+    assert_eq!(lexord::from_slice::<Cached>(b"").unwrap().0, 0);
+}
+
+#[test]
+fn test_generic_bounds() {
+    use std::marker::PhantomData;
+
+    #[derive(LexOrd, Debug)]
+    struct Key<T> {
+        id: T,
+        #[lexord(desc)]
+        version: T,
+    }
+
+    #[derive(LexOrd, Debug)]
+    enum Either<L, R> {
+        Left(L),
+        Right(R),
+    }
+
+    trait Table {}
+
+    #[derive(Debug)]
+    struct Users;
+
+    impl Table for Users {}
+
+    #[derive(LexOrd, Debug)]
+    #[lexord(bound(ord = "", ser = "", de = "", de_ref = ""))]
+    struct Row<T: Table> {
+        id: u32,
+        #[lexord(skip)]
+        table: PhantomData<T>,
+    }
+
+    assert_snapshot!(encode(Key { id: 1u8, version: 2u8 }), @"01 FD");
+    assert_snapshot!(encode(Key { id: "a".to_string(), version: "b".to_string() }), @"61 00 9D FF");
+    assert_snapshot!(encode(Either::<u8, String>::Right("a".to_string())), @"81 61 00");
+    assert!(Either::<u8, String>::Left(9) < Either::Right(String::new()));
+    let row = Row::<Users> {
+        id: 3,
+        table: PhantomData,
+    };
+    let bytes = lexord::to_vec(&row).unwrap();
+    assert_eq!(lexord::from_slice::<Row<Users>>(&bytes).unwrap(), row);
+    assert_eq!(
+        lexord::from_reader::<Row<Users>>(bytes.as_slice()).unwrap(),
+        row
+    );
+}
+
+#[test]
+fn test_cmp_modes() {
+    use std::cmp::Ordering;
+    use std::collections::BTreeSet;
+
+    #[derive(LexOrd, Debug)]
+    #[lexord(no_cmp)]
+    struct Name {
+        last: String,
+        first: String,
+    }
+
+    impl PartialEq for Name {
+        fn eq(&self, other: &Self) -> bool {
+            self.partial_cmp(other) == Some(Ordering::Equal)
+        }
+    }
+
+    impl PartialOrd for Name {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some((&self.last, &self.first).cmp(&(&other.last, &other.first)))
+        }
+    }
+
+    #[derive(LexOrd, Debug, Clone)]
+    #[lexord(ord)]
+    enum Key {
+        User {
+            id: u32,
+            #[lexord(desc)]
+            version: u16,
+        },
+        Group(String),
+    }
+
+    let name = |last: &str, first: &str| Name {
+        last: last.to_string(),
+        first: first.to_string(),
+    };
+    assert_snapshot!(encode(name("b", "a")), @"62 00 61 00");
+    check_order(&[name("", ""), name("a", "b"), name("a", "ba"), name("b", "")]);
+
+    let keys = [
+        Key::Group("b".to_string()),
+        Key::User { id: 2, version: 1 },
+        Key::User { id: 1, version: 1 },
+        Key::Group("a".to_string()),
+        Key::User { id: 1, version: 2 },
+    ];
+    check_order(&keys);
+    let sorted: Vec<_> = keys
+        .iter()
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    assert_snapshot!(format!("{sorted:?}"), @r#"[User { id: 1, version: 2 }, User { id: 1, version: 1 }, User { id: 2, version: 1 }, Group("a"), Group("b")]"#);
+}
+
+#[test]
+fn test_derive_ser() {
+    #[derive(LexOrd, Debug)]
+    struct Key {
+        tenant: String,
+        path: Vec<u8>,
+        #[lexord(desc)]
+        version: u32,
+    }
+
+    #[derive(LexOrdSer, Debug)]
+    #[lexord(encodes_as = "Key")]
+    struct KeyRef<'a> {
+        tenant: &'a str,
+        path: &'a [u8],
+        #[lexord(desc)]
+        version: u32,
+    }
+
+    #[derive(LexOrdSer, Debug)]
+    enum EventRef<'a, T: ?Sized> {
+        Put(&'a T),
+        Delete,
+    }
+
+    let key = Key {
+        tenant: "a".to_string(),
+        path: b"\0x".to_vec(),
+        version: 3,
+    };
+    let key_ref = KeyRef {
+        tenant: "a",
+        path: b"\0x",
+        version: 3,
+    };
+    let key_bytes = lexord::to_vec(&key).unwrap();
+    assert_snapshot!(encode(key), @"61 00 01 00 78 00 7C");
+    assert_eq!(lexord::to_vec_as::<Key, _>(&key_ref).unwrap(), key_bytes);
+    assert_eq!(
+        key_ref.encoded_len(),
+        lexord::to_vec(&key_ref).unwrap().len()
+    );
+    check_order(&[
+        KeyRef {
+            tenant: "a",
+            path: b"",
+            version: 1,
+        },
+        KeyRef {
+            tenant: "a",
+            path: b"",
+            version: 0,
+        },
+        KeyRef {
+            tenant: "a",
+            path: b"\0",
+            version: 9,
+        },
+        KeyRef {
+            tenant: "b",
+            path: b"",
+            version: 0,
+        },
+    ]);
+    check_encodes_as::<Key, _>(&[
+        key_ref,
+        KeyRef {
+            tenant: "",
+            path: b"\x01",
+            version: u32::MAX,
+        },
+    ]);
+
+    #[derive(LexOrd, Debug)]
+    struct Point {
+        x: f64,
+    }
+
+    #[derive(LexOrdSer, Debug)]
+    #[lexord(encodes_as = "Point")]
+    struct PointRef<'a> {
+        x: &'a f64,
+    }
+
+    check_encodes_as::<Point, _>(&[PointRef { x: &1.5 }, PointRef { x: &-f64::NAN }]);
+    check_order(&[EventRef::Put("a"), EventRef::Put("b"), EventRef::Delete]);
+    assert_eq!(EventRef::<str>::Delete.encoded_len(), 1);
+
+    #[derive(LexOrdSer, Debug)]
+    #[lexord(encodes_as = "Key")]
+    struct MissingPath<'a> {
+        tenant: &'a str,
+        #[lexord(desc)]
+        version: u32,
+    }
+
+    let error = std::panic::catch_unwind(|| {
+        check_encodes_as::<Key, _>(&[MissingPath {
+            tenant: "a",
+            version: 3,
+        }])
+    })
+    .unwrap_err();
+    let message = error.downcast_ref::<String>().unwrap();
+    assert_snapshot!(message, @r#"MissingPath { tenant: "a", version: 3 } ([61, 0, 7c]) is not a derive_test::test_derive_ser::Key: Unexpected end of input after 3 bytes in Key.path"#);
+}
+
+#[test]
+fn test_recursive_types() {
+    use std::sync::Arc;
+
+    #[derive(LexOrd, Debug)]
+    enum Expr {
+        Num(u32),
+        Neg(Box<Expr>),
+        Add(Box<Expr>, Box<Expr>),
+    }
+
+    #[derive(LexOrd, Debug)]
+    struct Tree<T> {
+        value: T,
+        children: Vec<Tree<T>>,
+        parent: Option<Box<Tree<T>>>,
+    }
+
+    #[derive(LexOrd, Debug)]
+    struct Path {
+        segments: Vec<Arc<str>>,
+    }
+
+    let num = |n| Box::new(Expr::Num(n));
+    assert_snapshot!(encode(Expr::Add(num(1), Box::new(Expr::Neg(num(2))))), @"82 80 81 81 80 82");
+    assert_eq!(Expr::MAX_ENCODED_LEN, None);
+    check_order(&[
+        Expr::Num(1),
+        Expr::Neg(num(0)),
+        Expr::Neg(num(1)),
+        Expr::Add(num(0), num(2)),
+        Expr::Add(num(1), num(0)),
+    ]);
+    let leaf = |value| Tree {
+        value,
+        children: vec![],
+        parent: None,
+    };
+    assert_snapshot!(encode(Tree {
+        value: 1u8,
+        children: vec![leaf(2)],
+        parent: Some(Box::new(leaf(0))),
+    }), @"01 02 00 80 00 81 00 00 80");
+    assert_snapshot!(encode(Path {
+        segments: vec!["a".into(), "".into()],
+    }), @"61 00 01 00 00");
+}
+
+#[test]
+fn test_with_codec() {
+    use std::cmp::Ordering;
+    use std::net::Ipv4Addr;
+
+    use lexord::io::{Sink, Source};
+    use lexord::LexOrdWith;
+
+    struct Ipv4;
+
+    impl LexOrdWith<Ipv4Addr> for Ipv4 {
+        const MAX_ENCODED_LEN: Option<usize> = Some(4);
+
+        fn to_write(value: &Ipv4Addr, writer: &mut impl Sink) -> lexord::Result {
+            writer.write_all(&value.octets())
+        }
+        fn from_read(reader: &mut impl Source) -> lexord::Result<Ipv4Addr> {
+            Ok(<[u8; 4]>::from_read(reader)?.into())
+        }
+        fn cmp(a: &Ipv4Addr, b: &Ipv4Addr) -> Ordering {
+            a.cmp(b)
+        }
+    }
+
+    // Orders by length first, unlike `Vec<T>` itself.
+    struct Shortlex;
+
+    impl<T: LexOrd + Ord> LexOrdWith<Vec<T>> for Shortlex {
+        fn to_write(value: &Vec<T>, writer: &mut impl Sink) -> lexord::Result {
+            (value.len(), value).to_write(writer)
+        }
+        fn from_read(reader: &mut impl Source) -> lexord::Result<Vec<T>> {
+            Ok(<(usize, Vec<T>)>::from_read(reader)?.1)
+        }
+        fn cmp(a: &Vec<T>, b: &Vec<T>) -> Ordering {
+            (a.len(), a).cmp(&(b.len(), b))
+        }
+    }
+
+    #[derive(LexOrd, Debug)]
+    struct Peer {
+        #[lexord(with = Ipv4)]
+        addr: Ipv4Addr,
+        #[lexord(with = "Shortlex", desc)]
+        ports: Vec<u16>,
+    }
+
+    #[derive(LexOrd, Debug)]
+    enum Path<T> {
+        Root,
+        Segments(#[lexord(with = Shortlex)] Vec<T>),
+    }
+
+    let peer = |addr: [u8; 4], ports: &[u16]| Peer {
+        addr: addr.into(),
+        ports: ports.to_vec(),
+    };
+    assert_snapshot!(encode(peer([10, 0, 0, 1], &[80])), @"0A 00 00 01 7E 3F AF FF");
+    assert_eq!(Peer::MAX_ENCODED_LEN, None);
+    check_order(&[
+        peer([9, 0, 0, 0], &[80, 443]),
+        peer([10, 0, 0, 1], &[1, 2]),
+        peer([10, 0, 0, 1], &[3]),
+        peer([10, 0, 0, 1], &[]),
+        peer([10, 0, 0, 2], &[]),
+    ]);
+    assert_snapshot!(encode(Path::Segments(vec![3u8, 1])), @"81 82 03 01 01 00");
+    check_order(&[
+        Path::Root,
+        Path::Segments(vec![9u8]),
+        Path::Segments(vec![1, 2]),
+        Path::Segments(vec![2, 0]),
+    ]);
+}
+
+#[test]
+fn test_transparent() {
+    use std::marker::PhantomData;
+
+    #[derive(LexOrd, Debug, Clone, Copy)]
+    #[lexord(transparent, ord)]
+    struct UserId(u64);
+
+    #[derive(LexOrd, Debug)]
+    #[lexord(transparent)]
+    struct Name {
+        value: String,
+    }
+
+    #[derive(LexOrd, Debug)]
+    #[lexord(transparent)]
+    struct Id<T> {
+        value: (u32, String),
+        #[lexord(skip)]
+        marker: PhantomData<T>,
+    }
+
+    assert_snapshot!(encode(UserId(1)), @"81");
+    assert_snapshot!(encode(vec![Name { value: "\0".to_string() }]), @"01 01 00 00 00");
+    assert_eq!(UserId::MAX_ENCODED_LEN, u64::MAX_ENCODED_LEN);
+    let ids = [0, 1, 0x80, u64::MAX].map(UserId);
+    check_order(&ids);
+    assert_eq!(
+        lexord::to_vec(&ids.to_vec()).unwrap(),
+        lexord::to_vec(&ids.map(|id| id.0).to_vec()).unwrap()
+    );
+    assert_eq!(ids.iter().max(), Some(&UserId(u64::MAX)));
+
+    let id = Id::<()> {
+        value: (7, "ab".to_string()),
+        marker: PhantomData,
+    };
+    let bytes = lexord::to_vec(&id).unwrap();
+    assert_snapshot!(encode(id), @"87 61 62 00");
+    let prefix = lexord::to_vec_prefix::<Id<()>, _>(&(7u32, StrPrefix("a"))).unwrap();
+    assert!(bytes.starts_with(&prefix));
+    let prefix = lexord::to_vec_prefix::<Id<()>, _>(&(7u32,)).unwrap();
+    assert_eq!(prefix, b"\x87");
+    let prefix = lexord::to_vec_prefix::<Id<()>, _>(&(7u32, "ab".to_string())).unwrap();
+    assert_eq!(prefix, bytes);
+    let prefix = lexord::to_vec_prefix::<Name, _>(&StrPrefix("a")).unwrap();
+    assert_eq!(prefix, b"a");
+    let prefix = lexord::to_vec_prefix::<UserId, _>(&5u64).unwrap();
+    assert_eq!(prefix, lexord::to_vec(&UserId(5)).unwrap());
+}
+
+#[test]
+fn test_field_order() {
+    #[derive(LexOrd, Debug)]
+    #[lexord(order(region, created_at, id, note))]
+    struct Order {
+        id: u32,
+        note: String,
+        region: String,
+        created_at: u64,
+    }
+
+    #[derive(LexOrd, Debug)]
+    struct Event(
+        #[lexord(position = 1)] u8,
+        #[lexord(skip)] bool,
+        #[lexord(position = 0, desc)] u16,
+    );
+
+    let order = |id, region: &str, created_at| Order {
+        id,
+        note: String::new(),
+        region: region.to_string(),
+        created_at,
+    };
+    assert_snapshot!(encode(order(1, "eu", 2)), @"65 75 00 82 81 00");
+    check_order(&[
+        order(3, "eu", 1),
+        order(1, "eu", 2),
+        order(2, "eu", 2),
+        order(0, "us", 0),
+    ]);
+    let prefix = lexord::to_vec_prefix::<Order, _>(&("eu".to_string(), 2u64)).unwrap();
+    assert!(lexord::to_vec(&order(1, "eu", 2))
+        .unwrap()
+        .starts_with(&prefix));
+    assert_snapshot!(encode(Event(1, false, 2)), @"7D 01");
+    assert!(!lexord::from_slice::<Event>(&[0x7D, 0x01]).unwrap().1);
+    check_order(&[Event(0, false, 2), Event(1, false, 2), Event(0, false, 1)]);
+}
+
+#[test]
+fn test_index_keys() {
+    #[derive(LexOrd, Debug)]
+    #[lexord(ord)]
+    #[lexord(indexes(
+        by_email(email, id, derive(Debug)),
+        by_age(age, id, derive(Debug, Clone))
+    ))]
+    struct User {
+        id: u32,
+        email: String,
+        #[lexord(desc)]
+        age: u8,
+    }
+
+    #[derive(LexOrd, Clone)]
+    struct Tenant(u16);
+
+    #[derive(LexOrd, Debug)]
+    #[lexord(indexes(by_second(1, derive(Debug))))]
+    struct Pair(u8, String);
+
+    #[derive(LexOrd)]
+    #[lexord(indexes(by_tenant(tenant, path)))]
+    struct Object {
+        path: String,
+        tenant: Tenant,
+    }
+
+    let user = User {
+        id: 7,
+        email: "a@b".to_string(),
+        age: 30,
+    };
+    let key = user.by_email_key();
+    assert_eq!((key.email.as_str(), key.id), ("a@b", 7));
+    assert_snapshot!(encode(user.by_email_key()), @"61 40 62 00 87");
+    assert_snapshot!(encode(user.by_age_key()), @"E1 87");
+    let users = [(1, "b", 20), (2, "a", 40), (3, "a", 20)].map(|(id, email, age)| User {
+        id,
+        email: email.to_string(),
+        age,
+    });
+    let mut by_age: Vec<_> = users.iter().map(User::by_age_key).collect();
+    by_age.sort();
+    assert_snapshot!(format!("{by_age:?}"), @"[ByAgeKey { age: 40, id: 2 }, ByAgeKey { age: 20, id: 1 }, ByAgeKey { age: 20, id: 3 }]");
+    check_order(&by_age);
+    assert_snapshot!(encode(Pair(1, "x".to_string()).by_second_key()), @"78 00");
+    let object = Object {
+        path: "a".to_string(),
+        tenant: Tenant(2),
+    };
+    let key = lexord::to_vec(&object.by_tenant_key()).unwrap();
+    assert_snapshot!(format!("{key:02X?}"), @"[82, 61, 00]");
+}
+
+#[test]
+fn test_borrowed_fields() {
+    use std::borrow::Cow;
+
+    #[derive(LexOrd, Debug)]
+    struct Borrowed<'a> {
+        name: Cow<'a, str>,
+        n: u32,
+    }
+
+    let value = Borrowed {
+        name: Cow::Borrowed("ab"),
+        n: 1,
+    };
+    let bytes = lexord::to_vec(&value).unwrap();
+    assert_snapshot!(format!("{bytes:02X?}"), @"[61, 62, 00, 81]");
+    let decoded = lexord::from_slice::<Borrowed>(&bytes).unwrap();
+    assert!(matches!(decoded.name, Cow::Borrowed("ab")));
+    assert_eq!(decoded, value);
+    let owned: Borrowed<'static> = lexord::from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(owned, value);
+    let escaped = lexord::to_vec(&Borrowed {
+        name: Cow::Borrowed("\0"),
+        n: 2,
+    })
+    .unwrap();
+    assert!(matches!(
+        lexord::from_slice::<Borrowed>(&escaped).unwrap().name,
+        Cow::Owned(_)
+    ));
+}
+
+#[test]
+fn test_cow_fields() {
+    use std::borrow::Cow;
+    use std::sync::Arc;
+
+    #[derive(LexOrd, Debug)]
+    enum Segment<'a, 'b> {
+        Name(Cow<'a, str>),
+        Bytes(Cow<'b, [u8]>),
+        Shared(Arc<str>),
+    }
+
+    let segments = [
+        Segment::Name(Cow::Borrowed("a")),
+        Segment::Name(Cow::Owned("b".to_string())),
+        Segment::Bytes(Cow::Borrowed(b"\0")),
+        Segment::Shared("a".into()),
+    ];
+    check_order(&segments);
+    let bytes = lexord::to_vec(segments.as_slice()).unwrap();
+    assert_snapshot!(format!("{bytes:02X?}"), @"[80, 61, 00, 80, 62, 00, 81, 01, 00, 00, 82, 61, 00, 00]");
+    let decoded = lexord::from_slice::<Vec<Segment>>(&bytes).unwrap();
+    assert_eq!(decoded, segments);
+    assert!(matches!(decoded[0], Segment::Name(Cow::Borrowed("a"))));
+    assert!(matches!(decoded[2], Segment::Bytes(Cow::Owned(_))));
+    let owned: Vec<Segment<'static, 'static>> = lexord::from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(owned, segments);
+}
+
+#[test]
+fn test_leading_option() {
+    #[derive(LexOrd, Debug)]
+    struct A {
+        a: Option<u8>,
+        b: u16,
+    }
+
+    assert_snapshot!(encode(A { a: None, b: 1 }), @"80 81");
+    assert_snapshot!(encode(vec![A { a: Some(0), b: 1 }, A { a: None, b: 2 }]), @"81 00 81 80 82 00");
+}
+
+#[test]
+fn test_error_path() {
+    #[derive(LexOrd, Debug)]
+    struct Item {
+        sku: u16,
+    }
+
+    #[derive(LexOrd, Debug)]
+    struct Order {
+        id: u8,
+        items: Vec<Item>,
+    }
+
+    #[derive(LexOrd, Debug)]
+    enum E {
+        A,
+        B(u8, Order),
+    }
+
+    let bytes = b"\x01\x81\x82\x83\x02\x00";
+    let error = lexord::from_slice::<Order>(bytes).unwrap_err();
+    assert_snapshot!(error, @"Unsupported VarInt prefix: 0x02 after 5 bytes in Order.items[3].sku");
+    let error = lexord::from_reader::<Order>(bytes.as_slice()).unwrap_err();
+    assert_snapshot!(error, @"Unsupported VarInt prefix: 0x02 after 5 bytes in Order.items[3].sku");
+    let error = lexord::from_slice::<E>(b"\x81\x00\x01\x81").unwrap_err();
+    assert_snapshot!(error, @"Unexpected end of input after 4 bytes in E.B.1.items");
+    let error = lexord::from_slice::<E>(b"\x85").unwrap_err();
+    assert_snapshot!(error, @"Unexpected enum variant: 5 after 1 bytes in E");
+    let error = lexord::from_slice::<(u8, E)>(b"\x00\x80\x00").unwrap_err();
+    assert_snapshot!(error, @"Trailing bytes: 1 after 2 bytes");
+}
+
+#[test]
+fn test_depth_limit() {
+    #[derive(LexOrd, Debug)]
+    enum Tree {
+        Leaf,
+        Node(Vec<Tree>),
+    }
+
+    let options = lexord::DecodeOptions {
+        max_depth: Some(64),
+        ..Default::default()
+    };
+    let mut bytes = vec![0x81; 3];
+    bytes.push(0x80);
+    bytes.extend([0x00; 3]);
+    assert!(lexord::from_slice_with::<Tree>(&bytes, options).is_ok());
+    let mut bytes = vec![0x81; 100_000];
+    bytes.push(0x80);
+    bytes.extend([0x00; 100_000]);
+    let error = lexord::from_slice_with::<Tree>(&bytes, options).unwrap_err();
+    assert!(matches!(error.kind(), lexord::ErrorKind::TooDeep(64)));
+    let error = lexord::from_reader_with::<Tree>(bytes.as_slice(), options).unwrap_err();
+    assert!(matches!(error.kind(), lexord::ErrorKind::TooDeep(64)));
+}
+
+#[test]
+fn test_max_encoded_len() {
+    #[derive(LexOrd, Debug)]
+    struct Key {
+        a: u16,
+        b: i8,
+    }
+
+    #[derive(LexOrd, Debug)]
+    enum E {
+        A,
+        B(u32, bool),
+        C { name: String },
+    }
+
+    assert_eq!(Key::MAX_ENCODED_LEN, Some(5));
+    assert_eq!(<Option<Key>>::MAX_ENCODED_LEN, Some(6));
+    assert_eq!(E::MAX_ENCODED_LEN, None);
+    assert_eq!(<Vec<Key>>::MAX_ENCODED_LEN, None);
+    let value = E::C {
+        name: "\0".to_string(),
+    };
+    assert_eq!(value.encoded_len(), 4);
+    assert_snapshot!(encode(value), @"82 01 00 00");
+    assert_snapshot!(encode(E::B(u32::MAX, true)), @"81 F0 00 00 00 FF FF FF FF 81");
+}
+
+#[test]
+fn test_prefix() {
+    #[derive(LexOrd, Debug)]
+    struct Key {
+        tenant: u64,
+        user: u64,
+        name: String,
+        ts: i64,
+    }
+
+    let key = Key {
+        tenant: 7,
+        user: 300,
+        name: "a".to_string(),
+        ts: -1,
+    };
+    let full = lexord::to_vec(&key).unwrap();
+    let prefix = lexord::to_vec_prefix::<Key, _>(&(7u64, 300u64)).unwrap();
+    assert_snapshot!(encode((7u64, 300u64)), @"87 C1 2C");
+    assert!(full.starts_with(&prefix));
+    let prefix = lexord::to_vec_prefix::<Key, _>(&(7u64, 300u64, "a".to_string())).unwrap();
+    assert_eq!(prefix, full[..full.len() - 1]);
+    let prefix = lexord::to_vec_prefix::<Key, _>(&(7u64, 300u64, StrPrefix(""))).unwrap();
+    assert_snapshot!(format!("{prefix:02X?}"), @"[87, C1, 2C]");
+    let byte_range = lexord::range::prefix::<Key, _>(&(7u64, 300u64, StrPrefix("ab"))).unwrap();
+    let contains = |name: &str| {
+        let key = Key {
+            tenant: 7,
+            user: 300,
+            name: name.to_string(),
+            ts: -1,
+        };
+        RangeBounds::<[u8]>::contains(&byte_range, lexord::to_vec(&key).unwrap().as_slice())
+    };
+    assert!(contains("ab") && contains("abc") && contains("ab\0"));
+    assert!(!contains("a") && !contains("b") && !contains("a\0b"));
+
+    #[derive(LexOrd, Debug)]
+    struct Blob {
+        id: u8,
+        data: Vec<u8>,
+    }
+    let prefix = lexord::to_vec_prefix::<Blob, _>(&(1u8, BytesPrefix(b"\0"))).unwrap();
+    assert_snapshot!(format!("{prefix:02X?}"), @"[01, 01, 00]");
+}